@@ -0,0 +1,181 @@
+// Achievement badge contract for TrueLens
+// This contract mints soulbound badge NFTs to verifiers who reach milestones.
+// It follows the SEP-style NFT interface, but badges can never be transferred.
+
+use soroban_sdk::{contract, contractimpl, contracttype, symbol_short, Address, Env, String, Vec};
+
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Achievement {
+    FirstVerification,
+    HundredAccurate,  // 100 verdicts matching consensus
+    Streak,           // A run of consecutive verdicts matching consensus
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum DataKey {
+    Admin,
+    Minter,                        // Contract allowed to award badges
+    NextTokenId,
+    Badge(u32),                    // Badge by token id
+    Owned(Address),                // Token ids held by an address
+    Held(Address, Achievement),    // Token id of an achievement held by an address
+    MetadataUri(Achievement),      // IPFS metadata URI per achievement
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Badge {
+    pub owner: Address,
+    pub achievement: Achievement,
+    pub minted_at: u64,
+}
+
+#[contract]
+pub struct AchievementBadges;
+
+#[contractimpl]
+impl AchievementBadges {
+    // Initialize the badge contract with the contract allowed to mint
+    pub fn initialize(env: Env, admin: Address, minter: Address) {
+        // Ensure the contract is not already initialized
+        if env.storage().instance().has(&DataKey::Admin) {
+            panic!("Contract already initialized");
+        }
+
+        env.storage().instance().set(&DataKey::Admin, &admin);
+        env.storage().instance().set(&DataKey::Minter, &minter);
+        env.storage().instance().set(&DataKey::NextTokenId, &0u32);
+    }
+
+    // Change the contract allowed to mint (admin only)
+    pub fn set_minter(env: Env, admin: Address, minter: Address) {
+        // Verify the admin is calling
+        let stored_admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        if admin != stored_admin {
+            panic!("Only admin can set the minter");
+        }
+        admin.require_auth();
+
+        env.storage().instance().set(&DataKey::Minter, &minter);
+    }
+
+    // Set the IPFS metadata URI for an achievement, e.g. "ipfs://<cid>" (admin only)
+    pub fn set_metadata_uri(env: Env, admin: Address, achievement: Achievement, uri: String) {
+        // Verify the admin is calling
+        let stored_admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        if admin != stored_admin {
+            panic!("Only admin can set metadata");
+        }
+        admin.require_auth();
+
+        env.storage().instance().set(&DataKey::MetadataUri(achievement), &uri);
+    }
+
+    // Mint an achievement badge (minter only). Each address holds at most one
+    // badge per achievement.
+    pub fn mint(env: Env, minter: Address, to: Address, achievement: Achievement) -> u32 {
+        let stored_minter: Address = env.storage().instance().get(&DataKey::Minter).unwrap();
+        if minter != stored_minter {
+            panic!("Only the minter can mint badges");
+        }
+        minter.require_auth();
+
+        if Self::has_badge(env.clone(), to.clone(), achievement) {
+            panic!("Badge already awarded");
+        }
+
+        let token_id: u32 = env.storage().instance().get(&DataKey::NextTokenId).unwrap();
+        env.storage().instance().set(&DataKey::NextTokenId, &(token_id + 1));
+
+        let badge = Badge {
+            owner: to.clone(),
+            achievement,
+            minted_at: env.ledger().timestamp(),
+        };
+        env.storage().persistent().set(&DataKey::Badge(token_id), &badge);
+        env.storage().persistent().set(&DataKey::Held(to.clone(), achievement), &token_id);
+
+        let mut owned = Self::get_badges(env.clone(), to.clone());
+        owned.push_back(token_id);
+        env.storage().persistent().set(&DataKey::Owned(to.clone()), &owned);
+
+        env.events().publish((symbol_short!("mint"), to), (token_id, achievement));
+
+        token_id
+    }
+
+    // Check whether an address holds an achievement's badge
+    pub fn has_badge(env: Env, owner: Address, achievement: Achievement) -> bool {
+        env.storage().persistent().has(&DataKey::Held(owner, achievement))
+    }
+
+    // Get the token ids of every badge an address holds
+    pub fn get_badges(env: Env, owner: Address) -> Vec<u32> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Owned(owner))
+            .unwrap_or_else(|| Vec::new(&env))
+    }
+
+    // Get a badge by token id
+    pub fn get_badge(env: Env, token_id: u32) -> Badge {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Badge(token_id))
+            .unwrap_or_else(|| panic!("Badge does not exist"))
+    }
+
+    // NFT interface
+
+    pub fn name(env: Env) -> String {
+        String::from_str(&env, "TrueLens Achievements")
+    }
+
+    pub fn symbol(env: Env) -> String {
+        String::from_str(&env, "TLBADGE")
+    }
+
+    pub fn balance(env: Env, owner: Address) -> u32 {
+        Self::get_badges(env, owner).len()
+    }
+
+    pub fn owner_of(env: Env, token_id: u32) -> Address {
+        Self::get_badge(env, token_id).owner
+    }
+
+    pub fn token_uri(env: Env, token_id: u32) -> String {
+        let badge = Self::get_badge(env.clone(), token_id);
+        env.storage()
+            .instance()
+            .get(&DataKey::MetadataUri(badge.achievement))
+            .unwrap_or_else(|| String::from_str(&env, ""))
+    }
+
+    pub fn get_approved(_env: Env, _token_id: u32) -> Option<Address> {
+        None
+    }
+
+    pub fn is_approved_for_all(_env: Env, _owner: Address, _operator: Address) -> bool {
+        false
+    }
+
+    // Badges are soulbound: transfers and approvals always fail
+
+    pub fn transfer(_env: Env, _from: Address, _to: Address, _token_id: u32) {
+        panic!("Badges are soulbound");
+    }
+
+    pub fn transfer_from(_env: Env, _spender: Address, _from: Address, _to: Address, _token_id: u32) {
+        panic!("Badges are soulbound");
+    }
+
+    pub fn approve(_env: Env, _owner: Address, _approved: Address, _token_id: u32, _live_until_ledger: u32) {
+        panic!("Badges are soulbound");
+    }
+
+    pub fn approve_for_all(_env: Env, _owner: Address, _operator: Address, _live_until_ledger: u32) {
+        panic!("Badges are soulbound");
+    }
+}
@@ -2,22 +2,35 @@
 // This contract creates and manages news verification instances
 
 #![no_std]
-use soroban_sdk::{contract, contractimpl, contracttype, Address, BytesN, Env, Symbol, Val, Vec};
+use soroban_sdk::{contract, contractimpl, contracttype, symbol_short, Address, BytesN, Env, Symbol, Val, Vec};
 
 // Export modules
 pub mod token;
+pub mod achievements;
 pub mod verification;
 pub mod smart_wallet;
+pub mod staking_vault;
 pub mod user_profile;
+pub mod wallet_factory;
+
+use user_profile::{ReputationSummary, UserProfileContractClient};
+
+// Protocol fees are expressed in basis points and capped at 10%
+const BPS_DENOMINATOR: i128 = 10_000;
+const MAX_FEE_BPS: u32 = 1_000;
 
 // Define the state of our verification
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub enum DataKey {
     OwnerAddress,
+    PendingOwner,
     NewsItems,
     Verifiers,
     VerificationState(BytesN<32>), // Verification for a specific news item
+    FeeConfig,
+    ProfileContract,  // User profile contract queried for reputation
+    TokenContract,    // TRUE token whose balances back delegated votes
 }
 
 // Representation of a news item
@@ -53,6 +66,34 @@ pub struct Verification {
     pub timestamp: u64,
 }
 
+// Where stake fees are sent
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum FeeRoute {
+    // Accrue as fees owed to the protocol treasury. Verification contracts
+    // hold no tokens, so the admin pays these via `settle_treasury_fees`.
+    Treasury,
+    // Fund the news item's own reward pool
+    RewardPool,
+}
+
+// Protocol fee schedule, read by verification contracts
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FeeConfig {
+    pub stake_fee_bps: u32,       // Taken from each verification stake
+    pub settlement_fee_bps: u32,  // Taken from settlement payouts, always to the treasury
+    pub route: FeeRoute,
+    pub treasury: Address,        // Receives treasury fees when they are settled
+    pub slash_share_bps: u32,     // Share of losing stakes paid to stakers as vault yield
+    pub staking_vault: Address,   // Vault that receives the slash share
+}
+
+// Fee owed on `amount` at `bps` basis points, rounded down
+pub(crate) fn fee_amount(amount: i128, bps: u32) -> i128 {
+    amount * bps as i128 / BPS_DENOMINATOR
+}
+
 #[contract]
 pub struct TrueLensContract;
 
@@ -61,7 +102,7 @@ impl TrueLensContract {
     // Initialize the contract
     pub fn initialize(env: Env, owner: Address) {
         // Ensure the contract is not already initialized
-        if env.storage().instance().has(&DataKey::OwnerAddress) {
+        if env.storage().instance().has(&DataKey::NewsItems) {
             panic!("Contract already initialized");
         }
 
@@ -73,6 +114,149 @@ impl TrueLensContract {
         env.storage().instance().set(&DataKey::Verifiers, &Vec::<Address>::new(&env));
     }
 
+    // Propose a new owner; takes effect once they call `accept_owner`
+    pub fn propose_owner(env: Env, owner: Address, new_owner: Address) {
+        // Verify the owner is calling
+        let stored_owner = Self::read_owner(&env);
+        if owner != stored_owner {
+            panic!("Only the owner can propose a new owner");
+        }
+        owner.require_auth();
+
+        env.storage().instance().set(&DataKey::PendingOwner, &new_owner);
+        env.events().publish((symbol_short!("owner"), symbol_short!("proposed")), (owner, new_owner));
+    }
+
+    // Complete the handover (only the proposed owner can call this)
+    pub fn accept_owner(env: Env) {
+        let new_owner: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::PendingOwner)
+            .unwrap_or_else(|| panic!("No owner transfer is pending"));
+        new_owner.require_auth();
+
+        let old_owner = Self::read_owner(&env);
+        env.storage().instance().set(&DataKey::OwnerAddress, &new_owner);
+        env.storage().instance().remove(&DataKey::PendingOwner);
+        env.events().publish((symbol_short!("owner"), symbol_short!("accepted")), (old_owner, new_owner));
+    }
+
+    // Permanently give up ownership (owner only)
+    pub fn renounce_owner(env: Env, owner: Address) {
+        // Verify the owner is calling
+        let stored_owner = Self::read_owner(&env);
+        if owner != stored_owner {
+            panic!("Only the owner can renounce ownership");
+        }
+        owner.require_auth();
+
+        env.storage().instance().remove(&DataKey::OwnerAddress);
+        env.storage().instance().remove(&DataKey::PendingOwner);
+        env.events().publish((symbol_short!("owner"), symbol_short!("renounced")), owner);
+    }
+
+    // Get the current owner (None once renounced)
+    pub fn get_owner(env: Env) -> Option<Address> {
+        env.storage().instance().get(&DataKey::OwnerAddress)
+    }
+
+    fn read_owner(env: &Env) -> Address {
+        env.storage()
+            .instance()
+            .get(&DataKey::OwnerAddress)
+            .unwrap_or_else(|| panic!("Ownership has been renounced"))
+    }
+
+    // Get the owner awaiting `accept_owner` (if any)
+    pub fn get_pending_owner(env: Env) -> Option<Address> {
+        env.storage().instance().get(&DataKey::PendingOwner)
+    }
+
+    // Set the protocol fee schedule (owner only)
+    pub fn set_fee_config(env: Env, owner: Address, config: FeeConfig) {
+        // Verify the owner is calling
+        let stored_owner = Self::read_owner(&env);
+        if owner != stored_owner {
+            panic!("Only the owner can set the fee config");
+        }
+        owner.require_auth();
+
+        if config.stake_fee_bps > MAX_FEE_BPS || config.settlement_fee_bps > MAX_FEE_BPS {
+            panic!("Fee exceeds the maximum");
+        }
+        if config.slash_share_bps as i128 > BPS_DENOMINATOR {
+            panic!("Slash share exceeds 100%");
+        }
+
+        env.storage().instance().set(&DataKey::FeeConfig, &config);
+        env.events().publish((symbol_short!("fees"), symbol_short!("updated")), config);
+    }
+
+    // Get the protocol fee schedule (None means no fees are charged)
+    pub fn get_fee_config(env: Env) -> Option<FeeConfig> {
+        env.storage().instance().get(&DataKey::FeeConfig)
+    }
+
+    // Get the fee charged on a verification stake of `amount`
+    pub fn quote_stake_fee(env: Env, amount: i128) -> i128 {
+        match Self::get_fee_config(env) {
+            Some(config) => fee_amount(amount, config.stake_fee_bps),
+            None => 0,
+        }
+    }
+
+    // Get the fee charged on a settlement payout of `amount`
+    pub fn quote_settlement_fee(env: Env, amount: i128) -> i128 {
+        match Self::get_fee_config(env) {
+            Some(config) => fee_amount(amount, config.settlement_fee_bps),
+            None => 0,
+        }
+    }
+
+    // Link the user profile contract used for reputation queries (owner only)
+    pub fn set_profile_contract(env: Env, owner: Address, profile_contract: Address) {
+        // Verify the owner is calling
+        let stored_owner = Self::read_owner(&env);
+        if owner != stored_owner {
+            panic!("Only the owner can set the profile contract");
+        }
+        owner.require_auth();
+
+        env.storage().instance().set(&DataKey::ProfileContract, &profile_contract);
+        env.events().publish((symbol_short!("profile"), symbol_short!("linked")), profile_contract);
+    }
+
+    // Get the linked user profile contract (if any)
+    pub fn get_profile_contract(env: Env) -> Option<Address> {
+        env.storage().instance().get(&DataKey::ProfileContract)
+    }
+
+    // Link the TRUE token whose balances back delegated votes (owner only)
+    pub fn set_token_contract(env: Env, owner: Address, token: Address) {
+        // Verify the owner is calling
+        let stored_owner = Self::read_owner(&env);
+        if owner != stored_owner {
+            panic!("Only the owner can set the token contract");
+        }
+        owner.require_auth();
+
+        env.storage().instance().set(&DataKey::TokenContract, &token);
+        env.events().publish((symbol_short!("token"), symbol_short!("linked")), token);
+    }
+
+    // Get the linked TRUE token (if any)
+    pub fn get_token_contract(env: Env) -> Option<Address> {
+        env.storage().instance().get(&DataKey::TokenContract)
+    }
+
+    // Get a user's reputation summary from the profile contract
+    // (None if no profile contract is linked or the user has no profile)
+    pub fn get_user_reputation(env: Env, user: Address) -> Option<ReputationSummary> {
+        let profile_contract = Self::get_profile_contract(env.clone())?;
+        UserProfileContractClient::new(&env, &profile_contract).get_reputation_summary(&user)
+    }
+
     // Submit a new news item for verification
     pub fn submit_news(
         env: Env,
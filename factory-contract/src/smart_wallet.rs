@@ -2,7 +2,17 @@
 // This implements a smart wallet that can be controlled by a user's passkey
 
 #![no_std]
-use soroban_sdk::{contract, contractimpl, contracttype, Address, BytesN, Env, String, Vec, Symbol, Map};
+use soroban_sdk::{contract, contractimpl, contracttype, symbol_short, vec, Address, BytesN, Env, IntoVal, String, Val, Vec, Symbol, Map};
+use soroban_sdk::auth::{ContractContext, InvokerContractAuthEntry, SubContractInvocation};
+
+use crate::staking_vault::StakingVaultClient;
+
+// Seconds a policy change waits before it can be applied, giving the owner
+// time to notice and cancel a change made with a compromised key
+const POLICY_CHANGE_DELAY: u64 = 2 * 24 * 60 * 60;
+
+// Upper bound on operations in a single batch authorization
+const MAX_BATCH_SIZE: u32 = 10;
 
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -11,7 +21,13 @@ pub enum DataKey {
     Guardian,
     Nonce,
     Authorizations,
-    AuthPolicy
+    AuthPolicy,
+    RecoveryConfig,
+    PendingRecovery,
+    SessionKeys,
+    SessionKey(Address),
+    PendingAuthPolicy,
+    PendingGuardian
 }
 
 #[contracttype]
@@ -27,17 +43,79 @@ pub enum AuthPolicy {
     MultiSig(Vec<Address>, u32), // Addresses and threshold
 }
 
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Operation {
+    pub operation: Symbol,
+    pub target: Address,
+    pub amount: i128,
+    pub data: Option<BytesN<32>>,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum AuthorizationPayload {
+    // A single operation
+    Single(Operation),
+    // Operations executed in order, all-or-nothing, under one approval
+    Batch(Vec<Operation>),
+}
+
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct Authorization {
     nonce: u64,
-    operation: Symbol,
-    target: Address,
-    amount: i128,
-    data: Option<BytesN<32>>,
+    payload: AuthorizationPayload,
     signatures: Vec<Address>
 }
 
+// An auth policy change waiting out its time-lock
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PendingAuthPolicy {
+    pub policy: AuthPolicy,
+    pub effective_at: u64,
+}
+
+// A guardian change waiting out the same time-lock as policy changes
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PendingGuardian {
+    pub guardian: Address,
+    pub effective_at: u64,
+}
+
+// Guardians allowed to rotate the owner when the owner's passkey is lost
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RecoveryConfig {
+    pub guardians: Vec<Address>,
+    pub threshold: u32,  // Number of guardian approvals required
+    pub delay: u64,      // Seconds the owner has to veto once the threshold is reached
+}
+
+// An owner rotation that guardians have started but not yet completed
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PendingRecovery {
+    pub new_owner: Address,
+    pub approvals: Vec<Address>,
+    pub initiated_at: u64,
+    pub executable_at: Option<u64>,  // Set once enough guardians have approved
+}
+
+// A temporary key the owner delegates a narrow set of operations to
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SessionKey {
+    pub key: Address,
+    pub contracts: Vec<Address>,  // Targets the key may call
+    pub operations: Vec<Symbol>,  // Wallet operations the key may run, e.g. `verify_news`
+    pub max_total_stake: i128,    // Cap on the summed amount across all uses
+    pub spent: i128,
+    pub expires_at: u64,
+}
+
 #[contract]
 pub struct SmartWallet;
 
@@ -79,7 +157,7 @@ impl SmartWallet {
         env.storage().instance().set(&DataKey::Owner, &new_owner);
     }
     
-    // Add a guardian address
+    // Propose a guardian address; it takes effect after the time-lock via `apply_guardian`
     pub fn add_guardian(env: Env, owner: Address, guardian: Address) {
         // Verify that owner is calling
         let stored_owner: Address = env.storage().instance().get(&DataKey::Owner).unwrap();
@@ -88,11 +166,89 @@ impl SmartWallet {
         }
         owner.require_auth();
         
-        // Store the guardian
-        env.storage().instance().set(&DataKey::Guardian, &guardian);
+        if guardian == owner {
+            panic!("The owner cannot be the guardian");
+        }
+        
+        // Under OwnerAndGuardian the guardian is a required co-signer, so the
+        // owner alone must not be able to swap it out
+        let policy: AuthPolicy = env.storage().instance().get(&DataKey::AuthPolicy).unwrap();
+        if policy == AuthPolicy::OwnerAndGuardian {
+            let current: Address = env.storage().instance().get(&DataKey::Guardian).unwrap();
+            current.require_auth();
+        }
+        
+        // Stage the guardian behind the time-lock
+        let effective_at = env.ledger().timestamp() + POLICY_CHANGE_DELAY;
+        let pending = PendingGuardian {
+            guardian: guardian.clone(),
+            effective_at,
+        };
+        env.storage().instance().set(&DataKey::PendingGuardian, &pending);
+        
+        env.events().publish(
+            (symbol_short!("guardian"), symbol_short!("proposed")),
+            (guardian, effective_at),
+        );
+    }
+    
+    // Apply the pending guardian once its time-lock has expired
+    pub fn apply_guardian(env: Env) -> Address {
+        let pending: PendingGuardian = env
+            .storage()
+            .instance()
+            .get(&DataKey::PendingGuardian)
+            .unwrap_or_else(|| panic!("No guardian change is pending"));
+        
+        if env.ledger().timestamp() < pending.effective_at {
+            panic!("Guardian time-lock has not expired");
+        }
+        
+        // Re-check in case the owner changed while the guardian was pending
+        let owner: Address = env.storage().instance().get(&DataKey::Owner).unwrap();
+        if pending.guardian == owner {
+            panic!("The owner cannot be the guardian");
+        }
+        
+        env.storage().instance().set(&DataKey::Guardian, &pending.guardian);
+        env.storage().instance().remove(&DataKey::PendingGuardian);
+        
+        env.events().publish(
+            (symbol_short!("guardian"), symbol_short!("applied")),
+            pending.guardian.clone(),
+        );
+        
+        pending.guardian
     }
     
-    // Update the auth policy
+    // Cancel the pending guardian change (owner, or the current guardian when
+    // the active policy needs its signature)
+    pub fn cancel_guardian(env: Env, signer: Address) {
+        let owner: Address = env.storage().instance().get(&DataKey::Owner).unwrap();
+        let policy: AuthPolicy = env.storage().instance().get(&DataKey::AuthPolicy).unwrap();
+        let guardian: Option<Address> = env.storage().instance().get(&DataKey::Guardian);
+        
+        let authorized = signer == owner
+            || (policy == AuthPolicy::OwnerAndGuardian && guardian == Some(signer.clone()));
+        if !authorized {
+            panic!("Signer cannot cancel a guardian change");
+        }
+        signer.require_auth();
+        
+        let pending: PendingGuardian = env
+            .storage()
+            .instance()
+            .get(&DataKey::PendingGuardian)
+            .unwrap_or_else(|| panic!("No guardian change is pending"));
+        env.storage().instance().remove(&DataKey::PendingGuardian);
+        
+        env.events().publish(
+            (symbol_short!("guardian"), symbol_short!("cancelled")),
+            pending.guardian,
+        );
+    }
+    
+    // Propose a new auth policy; it takes effect after the time-lock via `apply_auth_policy`
     pub fn update_auth_policy(env: Env, owner: Address, policy: AuthPolicy) {
         // Verify that owner is calling
         let stored_owner: Address = env.storage().instance().get(&DataKey::Owner).unwrap();
@@ -101,8 +257,402 @@ impl SmartWallet {
         }
         owner.require_auth();
         
-        // Store the policy
-        env.storage().instance().set(&DataKey::AuthPolicy, &policy);
+        // Reject policies that could never be satisfied
+        Self::validate_policy(&env, &policy);
+        
+        // Stage the policy behind the time-lock
+        let effective_at = env.ledger().timestamp() + POLICY_CHANGE_DELAY;
+        let pending = PendingAuthPolicy {
+            policy: policy.clone(),
+            effective_at,
+        };
+        env.storage().instance().set(&DataKey::PendingAuthPolicy, &pending);
+        
+        env.events().publish(
+            (symbol_short!("policy"), symbol_short!("proposed")),
+            (policy, effective_at),
+        );
+    }
+    
+    // Apply the pending auth policy once its time-lock has expired
+    pub fn apply_auth_policy(env: Env) -> AuthPolicy {
+        let pending: PendingAuthPolicy = env
+            .storage()
+            .instance()
+            .get(&DataKey::PendingAuthPolicy)
+            .unwrap_or_else(|| panic!("No auth policy change is pending"));
+        
+        if env.ledger().timestamp() < pending.effective_at {
+            panic!("Auth policy time-lock has not expired");
+        }
+        
+        // Re-check in case the wallet changed while the policy was pending
+        Self::validate_policy(&env, &pending.policy);
+        
+        env.storage().instance().set(&DataKey::AuthPolicy, &pending.policy);
+        env.storage().instance().remove(&DataKey::PendingAuthPolicy);
+        
+        env.events().publish(
+            (symbol_short!("policy"), symbol_short!("applied")),
+            pending.policy.clone(),
+        );
+        
+        pending.policy
+    }
+    
+    // Cancel the pending auth policy change (owner only)
+    pub fn cancel_auth_policy(env: Env, owner: Address) {
+        // Verify that owner is calling
+        let stored_owner: Address = env.storage().instance().get(&DataKey::Owner).unwrap();
+        if owner != stored_owner {
+            panic!("Only the owner can cancel an auth policy change");
+        }
+        owner.require_auth();
+        
+        let pending: PendingAuthPolicy = env
+            .storage()
+            .instance()
+            .get(&DataKey::PendingAuthPolicy)
+            .unwrap_or_else(|| panic!("No auth policy change is pending"));
+        env.storage().instance().remove(&DataKey::PendingAuthPolicy);
+        
+        env.events().publish(
+            (symbol_short!("policy"), symbol_short!("cancelled")),
+            pending.policy,
+        );
+    }
+    
+    // Panic if the policy can never be satisfied by this wallet
+    fn validate_policy(env: &Env, policy: &AuthPolicy) {
+        match policy {
+            AuthPolicy::OwnerOnly => {},
+            AuthPolicy::OwnerOrGuardian | AuthPolicy::OwnerAndGuardian => {
+                if !env.storage().instance().has(&DataKey::Guardian) {
+                    panic!("A guardian must be set before using a guardian policy");
+                }
+            },
+            AuthPolicy::MultiSig(addresses, threshold) => {
+                if *threshold == 0 || *threshold > addresses.len() {
+                    panic!("MultiSig threshold must be between 1 and the number of addresses");
+                }
+                for i in 0..addresses.len() {
+                    let address = addresses.get(i).unwrap();
+                    for j in (i + 1)..addresses.len() {
+                        if addresses.get(j).unwrap() == address {
+                            panic!("Duplicate MultiSig address");
+                        }
+                    }
+                }
+            }
+        }
+    }
+    
+    // Configure the guardians that can recover the wallet
+    pub fn set_recovery_config(env: Env, owner: Address, guardians: Vec<Address>, threshold: u32, delay: u64) {
+        // Verify that owner is calling
+        let stored_owner: Address = env.storage().instance().get(&DataKey::Owner).unwrap();
+        if owner != stored_owner {
+            panic!("Only the owner can configure recovery");
+        }
+        owner.require_auth();
+        
+        // The configuration cannot change underneath an in-flight recovery
+        if env.storage().instance().has(&DataKey::PendingRecovery) {
+            panic!("Cannot change recovery config while a recovery is pending");
+        }
+        
+        // Validate the guardian set and threshold
+        if threshold == 0 || threshold > guardians.len() {
+            panic!("Recovery threshold must be between 1 and the number of guardians");
+        }
+        
+        for i in 0..guardians.len() {
+            let guardian = guardians.get(i).unwrap();
+            if guardian == owner {
+                panic!("The owner cannot be a recovery guardian");
+            }
+            for j in (i + 1)..guardians.len() {
+                if guardians.get(j).unwrap() == guardian {
+                    panic!("Duplicate recovery guardian");
+                }
+            }
+        }
+        
+        let config = RecoveryConfig {
+            guardians,
+            threshold,
+            delay,
+        };
+        env.storage().instance().set(&DataKey::RecoveryConfig, &config);
+    }
+    
+    // Start rotating the owner to a new address (guardians only)
+    pub fn initiate_recovery(env: Env, guardian: Address, new_owner: Address) {
+        let config = Self::require_recovery_guardian(&env, &guardian);
+        guardian.require_auth();
+        
+        if env.storage().instance().has(&DataKey::PendingRecovery) {
+            panic!("A recovery is already pending");
+        }
+        
+        let owner: Address = env.storage().instance().get(&DataKey::Owner).unwrap();
+        if new_owner == owner {
+            panic!("New owner must differ from the current owner");
+        }
+        
+        // The initiating guardian counts as the first approval
+        let mut approvals = Vec::new(&env);
+        approvals.push_back(guardian.clone());
+        
+        let now = env.ledger().timestamp();
+        let executable_at = if config.threshold <= 1 {
+            Some(now + config.delay)
+        } else {
+            None
+        };
+        
+        let recovery = PendingRecovery {
+            new_owner: new_owner.clone(),
+            approvals,
+            initiated_at: now,
+            executable_at,
+        };
+        env.storage().instance().set(&DataKey::PendingRecovery, &recovery);
+        
+        env.events().publish(
+            (symbol_short!("recovery"), symbol_short!("initiated")),
+            (guardian, new_owner),
+        );
+    }
+    
+    // Approve the pending recovery (guardians only)
+    pub fn approve_recovery(env: Env, guardian: Address) {
+        let config = Self::require_recovery_guardian(&env, &guardian);
+        guardian.require_auth();
+        
+        let mut recovery: PendingRecovery = env
+            .storage()
+            .instance()
+            .get(&DataKey::PendingRecovery)
+            .unwrap_or_else(|| panic!("No recovery is pending"));
+        
+        if recovery.approvals.contains(&guardian) {
+            panic!("Guardian has already approved this recovery");
+        }
+        recovery.approvals.push_back(guardian.clone());
+        
+        // Start the veto window once the threshold is reached
+        if recovery.executable_at.is_none() && recovery.approvals.len() >= config.threshold {
+            recovery.executable_at = Some(env.ledger().timestamp() + config.delay);
+        }
+        
+        let approval_count = recovery.approvals.len();
+        env.storage().instance().set(&DataKey::PendingRecovery, &recovery);
+        
+        env.events().publish(
+            (symbol_short!("recovery"), symbol_short!("approved")),
+            (guardian, approval_count),
+        );
+    }
+    
+    // Veto the pending recovery (owner only)
+    pub fn cancel_recovery(env: Env, owner: Address) {
+        // Verify that owner is calling
+        let stored_owner: Address = env.storage().instance().get(&DataKey::Owner).unwrap();
+        if owner != stored_owner {
+            panic!("Only the owner can cancel a recovery");
+        }
+        owner.require_auth();
+        
+        let recovery: PendingRecovery = env
+            .storage()
+            .instance()
+            .get(&DataKey::PendingRecovery)
+            .unwrap_or_else(|| panic!("No recovery is pending"));
+        env.storage().instance().remove(&DataKey::PendingRecovery);
+        
+        env.events().publish(
+            (symbol_short!("recovery"), symbol_short!("cancelled")),
+            (owner, recovery.new_owner),
+        );
+    }
+    
+    // Complete the recovery once the threshold is met and the veto window has passed
+    pub fn complete_recovery(env: Env) -> Address {
+        let recovery: PendingRecovery = env
+            .storage()
+            .instance()
+            .get(&DataKey::PendingRecovery)
+            .unwrap_or_else(|| panic!("No recovery is pending"));
+        
+        let executable_at = recovery
+            .executable_at
+            .unwrap_or_else(|| panic!("Recovery has not reached the guardian threshold"));
+        if env.ledger().timestamp() < executable_at {
+            panic!("Recovery time-lock has not expired");
+        }
+        
+        let old_owner: Address = env.storage().instance().get(&DataKey::Owner).unwrap();
+        env.storage().instance().set(&DataKey::Owner, &recovery.new_owner);
+        env.storage().instance().remove(&DataKey::PendingRecovery);
+        
+        // Drop authorizations, session keys and any auth policy or guardian
+        // change that were granted or queued with the lost key
+        env.storage().instance().set(&DataKey::Authorizations, &Vec::<Authorization>::new(&env));
+        env.storage().instance().remove(&DataKey::PendingAuthPolicy);
+        env.storage().instance().remove(&DataKey::PendingGuardian);
+        let session_keys: Vec<Address> = env.storage().instance().get(&DataKey::SessionKeys).unwrap_or_else(|| Vec::new(&env));
+        for key in session_keys.iter() {
+            Self::remove_session_key(&env, &key);
+        }
+        
+        env.events().publish(
+            (symbol_short!("recovery"), symbol_short!("completed")),
+            (old_owner, recovery.new_owner.clone()),
+        );
+        
+        recovery.new_owner
+    }
+    
+    // Load the recovery config and check that the address is one of its guardians
+    fn require_recovery_guardian(env: &Env, guardian: &Address) -> RecoveryConfig {
+        let config: RecoveryConfig = env
+            .storage()
+            .instance()
+            .get(&DataKey::RecoveryConfig)
+            .unwrap_or_else(|| panic!("Recovery is not configured"));
+        
+        if !config.guardians.contains(guardian) {
+            panic!("Signer is not a recovery guardian");
+        }
+        
+        config
+    }
+    
+    // Grant a scoped, expiring session key (owner only)
+    pub fn grant_session_key(
+        env: Env,
+        owner: Address,
+        key: Address,
+        contracts: Vec<Address>,
+        operations: Vec<Symbol>,
+        max_total_stake: i128,
+        expires_at: u64
+    ) {
+        // Verify that owner is calling
+        let stored_owner: Address = env.storage().instance().get(&DataKey::Owner).unwrap();
+        if owner != stored_owner {
+            panic!("Only the owner can grant session keys");
+        }
+        owner.require_auth();
+        
+        if key == owner {
+            panic!("The owner cannot be a session key");
+        }
+        if contracts.is_empty() || operations.is_empty() {
+            panic!("Session key must be scoped to at least one contract and operation");
+        }
+        if max_total_stake < 0 {
+            panic!("Max total stake must be non-negative");
+        }
+        if expires_at <= env.ledger().timestamp() {
+            panic!("Session key expiry must be in the future");
+        }
+        
+        // Granting an existing key replaces its scope and resets its spend
+        let session = SessionKey {
+            key: key.clone(),
+            contracts,
+            operations,
+            max_total_stake,
+            spent: 0,
+            expires_at,
+        };
+        env.storage().instance().set(&DataKey::SessionKey(key.clone()), &session);
+        
+        let mut keys: Vec<Address> = env.storage().instance().get(&DataKey::SessionKeys).unwrap_or_else(|| Vec::new(&env));
+        if !keys.contains(&key) {
+            keys.push_back(key.clone());
+            env.storage().instance().set(&DataKey::SessionKeys, &keys);
+        }
+        
+        env.events().publish(
+            (symbol_short!("session"), symbol_short!("granted")),
+            (key, max_total_stake, expires_at),
+        );
+    }
+    
+    // Revoke a session key (owner only)
+    pub fn revoke_session_key(env: Env, owner: Address, key: Address) {
+        // Verify that owner is calling
+        let stored_owner: Address = env.storage().instance().get(&DataKey::Owner).unwrap();
+        if owner != stored_owner {
+            panic!("Only the owner can revoke session keys");
+        }
+        owner.require_auth();
+        
+        if !env.storage().instance().has(&DataKey::SessionKey(key.clone())) {
+            panic!("Session key not found");
+        }
+        
+        Self::remove_session_key(&env, &key);
+        
+        env.events().publish(
+            (symbol_short!("session"), symbol_short!("revoked")),
+            key,
+        );
+    }
+    
+    // Execute an operation signed only by a session key, within its scope
+    pub fn execute_session_operation(
+        env: Env,
+        key: Address,
+        operation: Symbol,
+        target: Address,
+        amount: i128,
+        data: Option<BytesN<32>>
+    ) -> Val {
+        key.require_auth();
+        
+        let mut session: SessionKey = env
+            .storage()
+            .instance()
+            .get(&DataKey::SessionKey(key.clone()))
+            .unwrap_or_else(|| panic!("Session key not found"));
+        
+        if env.ledger().timestamp() >= session.expires_at {
+            panic!("Session key has expired");
+        }
+        if !session.contracts.contains(&target) {
+            panic!("Session key is not allowed to call this contract");
+        }
+        if !session.operations.contains(&operation) {
+            panic!("Session key is not allowed to run this operation");
+        }
+        if amount < 0 {
+            panic!("Amount must be non-negative");
+        }
+        
+        // Enforce the cumulative spend cap
+        let spent = session.spent + amount;
+        if spent > session.max_total_stake {
+            panic!("Session key stake limit exceeded");
+        }
+        session.spent = spent;
+        env.storage().instance().set(&DataKey::SessionKey(key), &session);
+        
+        Self::execute_operation(&env, operation, target, amount, data)
+    }
+    
+    // Delete a session key and drop it from the index
+    fn remove_session_key(env: &Env, key: &Address) {
+        env.storage().instance().remove(&DataKey::SessionKey(key.clone()));
+        
+        let mut keys: Vec<Address> = env.storage().instance().get(&DataKey::SessionKeys).unwrap_or_else(|| Vec::new(env));
+        if let Some(index) = keys.first_index_of(key) {
+            keys.remove(index);
+            env.storage().instance().set(&DataKey::SessionKeys, &keys);
+        }
     }
     
     // Submit a transaction authorization
@@ -114,20 +664,32 @@ impl SmartWallet {
         amount: i128,
         data: Option<BytesN<32>>
     ) -> u64 {
-        // Verify that the signer is either the owner or the guardian
-        let owner: Address = env.storage().instance().get(&DataKey::Owner).unwrap();
-        let authorized: bool;
+        let payload = AuthorizationPayload::Single(Operation {
+            operation,
+            target,
+            amount,
+            data,
+        });
         
-        if signer == owner {
-            authorized = true;
-        } else if env.storage().instance().has(&DataKey::Guardian) {
-            let guardian: Address = env.storage().instance().get(&DataKey::Guardian).unwrap();
-            authorized = (signer == guardian);
-        } else {
-            authorized = false;
+        Self::store_authorization(&env, signer, payload)
+    }
+    
+    // Submit several operations to be approved and executed together
+    pub fn submit_batch_authorization(env: Env, signer: Address, operations: Vec<Operation>) -> u64 {
+        if operations.is_empty() {
+            panic!("Batch must contain at least one operation");
+        }
+        if operations.len() > MAX_BATCH_SIZE {
+            panic!("Batch exceeds the maximum number of operations");
         }
         
-        if !authorized {
+        Self::store_authorization(&env, signer, AuthorizationPayload::Batch(operations))
+    }
+    
+    // Record a new authorization signed by the submitter and return its nonce
+    fn store_authorization(env: &Env, signer: Address, payload: AuthorizationPayload) -> u64 {
+        // Verify that the signer is the owner, the guardian or a MultiSig member
+        if !Self::is_signer(env, &signer) {
             panic!("Signer is not authorized to submit transactions");
         }
         
@@ -138,16 +700,13 @@ impl SmartWallet {
         let nonce: u64 = env.storage().instance().get(&DataKey::Nonce).unwrap();
         
         // Create a list with the current signer
-        let mut signatures = Vec::new(&env);
+        let mut signatures = Vec::new(env);
         signatures.push_back(signer);
         
         // Create the authorization
         let auth = Authorization {
             nonce,
-            operation,
-            target,
-            amount,
-            data,
+            payload,
             signatures
         };
         
@@ -164,22 +723,29 @@ impl SmartWallet {
         nonce
     }
     
-    // Approve an existing authorization
-    pub fn approve_authorization(env: Env, signer: Address, nonce: u64) {
-        // Verify that the signer is either the owner or the guardian
+    // Whether the address may submit or approve authorizations. Members of an
+    // active MultiSig policy can sign so that its threshold is reachable.
+    fn is_signer(env: &Env, signer: &Address) -> bool {
         let owner: Address = env.storage().instance().get(&DataKey::Owner).unwrap();
-        let authorized: bool;
+        if *signer == owner {
+            return true;
+        }
         
-        if signer == owner {
-            authorized = true;
-        } else if env.storage().instance().has(&DataKey::Guardian) {
-            let guardian: Address = env.storage().instance().get(&DataKey::Guardian).unwrap();
-            authorized = (signer == guardian);
-        } else {
-            authorized = false;
+        let guardian: Option<Address> = env.storage().instance().get(&DataKey::Guardian);
+        if guardian.as_ref() == Some(signer) {
+            return true;
         }
         
-        if !authorized {
+        match env.storage().instance().get(&DataKey::AuthPolicy).unwrap() {
+            AuthPolicy::MultiSig(addresses, _) => addresses.contains(signer),
+            _ => false,
+        }
+    }
+    
+    // Approve an existing authorization
+    pub fn approve_authorization(env: Env, signer: Address, nonce: u64) {
+        // Verify that the signer is the owner, the guardian or a MultiSig member
+        if !Self::is_signer(&env, &signer) {
             panic!("Signer is not authorized to approve transactions");
         }
         
@@ -216,8 +782,9 @@ impl SmartWallet {
         env.storage().instance().set(&DataKey::Authorizations, &authorizations);
     }
     
-    // Execute an authorization if it meets the policy requirements
-    pub fn execute_authorization(env: Env, nonce: u64) {
+    // Execute an authorization if it meets the policy requirements, returning
+    // one result per executed operation
+    pub fn execute_authorization(env: Env, nonce: u64) -> Vec<Val> {
         // Find the authorization
         let mut authorizations: Vec<Authorization> = env.storage().instance().get(&DataKey::Authorizations).unwrap();
         let mut auth_index = None;
@@ -288,23 +855,16 @@ impl SmartWallet {
                 meets_policy = owner_signed && guardian_signed;
             },
             AuthPolicy::MultiSig(addresses, threshold) => {
-                // Check if enough authorized signers have signed
-                let mut valid_sigs = 0;
+                // Count each authorized signer at most once
+                let mut counted: Vec<Address> = Vec::new(&env);
                 
-                for i in 0..auth.signatures.len() {
-                    let signer = auth.signatures.get_unchecked(i).unwrap();
-                    
-                    // Check if the signer is in the authorized list
-                    for j in 0..addresses.len() {
-                        let authorized = addresses.get_unchecked(j).unwrap();
-                        if signer == authorized {
-                            valid_sigs += 1;
-                            break;
-                        }
+                for signer in auth.signatures.iter() {
+                    if addresses.contains(&signer) && !counted.contains(&signer) {
+                        counted.push_back(signer);
                     }
                 }
                 
-                meets_policy = valid_sigs >= threshold;
+                meets_policy = counted.len() >= threshold;
             }
         }
         
@@ -312,56 +872,83 @@ impl SmartWallet {
             panic!("Authorization does not meet the policy requirements");
         }
         
-        // Execute the operation
-        match auth.operation.as_ref() {
-            "transfer" => {
-                // Transfer funds to the target
-                Self::execute_transfer(env.clone(), auth.target, auth.amount);
-            },
-            "verify_news" => {
-                // Verify news using the TrueLens verification contract
-                Self::execute_verify_news(env.clone(), auth.target, auth.data.unwrap_or_else(|| panic!("Data is required for verify_news")));
-            },
-            "stake" => {
-                // Stake XLM
-                Self::execute_stake(env.clone(), auth.target, auth.amount);
+        // Remove the authorization before running it so it cannot be re-entered
+        let idx = auth_index.unwrap();
+        authorizations.remove(idx);
+        env.storage().instance().set(&DataKey::Authorizations, &authorizations);
+        
+        // Execute the operations in order; any failure reverts the whole batch
+        let mut results = Vec::new(&env);
+        match auth.payload {
+            AuthorizationPayload::Single(op) => {
+                results.push_back(Self::execute_operation(&env, op.operation, op.target, op.amount, op.data));
             },
-            _ => {
-                panic!("Unsupported operation");
+            AuthorizationPayload::Batch(ops) => {
+                for op in ops.iter() {
+                    results.push_back(Self::execute_operation(&env, op.operation, op.target, op.amount, op.data));
+                }
             }
         }
         
-        // Remove the executed authorization
-        let idx = auth_index.unwrap();
-        authorizations.remove(idx);
-        env.storage().instance().set(&DataKey::Authorizations, &authorizations);
+        results
+    }
+    
+    // Dispatch a single wallet operation to its handler and return its result
+    fn execute_operation(env: &Env, operation: Symbol, target: Address, amount: i128, data: Option<BytesN<32>>) -> Val {
+        if operation == symbol_short!("transfer") {
+            // Transfer funds to the target
+            Self::execute_transfer(env.clone(), target, amount)
+        } else if operation == Symbol::new(env, "verify_news") {
+            // Verify news using the TrueLens verification contract
+            Self::execute_verify_news(env.clone(), target, data.unwrap_or_else(|| panic!("Data is required for verify_news")))
+        } else if operation == symbol_short!("stake") {
+            // Stake TRUE in the staking vault
+            Self::execute_stake(env.clone(), target, amount)
+        } else {
+            panic!("Unsupported operation");
+        }
     }
     
     // Execute a transfer operation
-    fn execute_transfer(env: Env, target: Address, amount: i128) {
+    fn execute_transfer(env: Env, target: Address, amount: i128) -> Val {
         // In a real implementation, this would transfer XLM or other tokens
         // to the target address.
-        let _ = env;
         let _ = target;
         let _ = amount;
+        ().into_val(&env)
     }
     
     // Execute a news verification operation
-    fn execute_verify_news(env: Env, verification_contract: Address, news_hash: BytesN<32>) {
+    fn execute_verify_news(env: Env, verification_contract: Address, news_hash: BytesN<32>) -> Val {
         // In a real implementation, this would call the TrueLens verification
         // contract to submit a verification.
-        let _ = env;
         let _ = verification_contract;
         let _ = news_hash;
+        ().into_val(&env)
     }
     
-    // Execute a staking operation
-    fn execute_stake(env: Env, staking_contract: Address, amount: i128) {
-        // In a real implementation, this would call the staking contract to
-        // stake the specified amount of XLM.
-        let _ = env;
-        let _ = staking_contract;
-        let _ = amount;
+    // Execute a staking operation by depositing TRUE into the staking vault,
+    // returning the vault shares minted
+    fn execute_stake(env: Env, staking_contract: Address, amount: i128) -> Val {
+        let vault = StakingVaultClient::new(&env, &staking_contract);
+        let wallet = env.current_contract_address();
+        
+        // The vault pulls the tokens from this wallet, so pre-authorize that transfer
+        let token = vault.get_token();
+        env.authorize_as_current_contract(vec![
+            &env,
+            InvokerContractAuthEntry::Contract(SubContractInvocation {
+                context: ContractContext {
+                    contract: token,
+                    fn_name: symbol_short!("transfer"),
+                    args: (wallet.clone(), staking_contract.clone(), amount).into_val(&env),
+                },
+                sub_invocations: vec![&env],
+            }),
+        ]);
+        
+        let shares = vault.deposit(&wallet, &amount, &None);
+        shares.into_val(&env)
     }
     
     // Get the current owner
@@ -388,6 +975,31 @@ impl SmartWallet {
         env.storage().instance().get(&DataKey::Nonce).unwrap()
     }
     
+    // Get the recovery configuration (if any)
+    pub fn get_recovery_config(env: Env) -> Option<RecoveryConfig> {
+        env.storage().instance().get(&DataKey::RecoveryConfig)
+    }
+    
+    // Get the pending recovery (if any)
+    pub fn get_pending_recovery(env: Env) -> Option<PendingRecovery> {
+        env.storage().instance().get(&DataKey::PendingRecovery)
+    }
+    
+    // Get the auth policy change waiting out its time-lock (if any)
+    pub fn get_pending_auth_policy(env: Env) -> Option<PendingAuthPolicy> {
+        env.storage().instance().get(&DataKey::PendingAuthPolicy)
+    }
+    
+    // Get a session key's scope and spend (if granted)
+    pub fn get_session_key(env: Env, key: Address) -> Option<SessionKey> {
+        env.storage().instance().get(&DataKey::SessionKey(key))
+    }
+    
+    // Get all granted session keys
+    pub fn get_session_keys(env: Env) -> Vec<Address> {
+        env.storage().instance().get(&DataKey::SessionKeys).unwrap_or_else(|| Vec::new(&env))
+    }
+    
     // Get a list of pending authorizations
     pub fn get_pending_authorizations(env: Env) -> Vec<Authorization> {
         env.storage().instance().get(&DataKey::Authorizations).unwrap()
@@ -0,0 +1,307 @@
+// Staking vault contract for TrueLens
+// This contract holds long-term TRUE stakes, optionally delegated to a news
+// source, and shares yield from slashed verification stakes among stakers
+
+use soroban_sdk::{contract, contractimpl, contracttype, symbol_short, token, Address, Env, Symbol, Vec};
+
+// Bound on pending unbonding entries per staker, keeping withdrawals cheap
+const MAX_UNBONDING_ENTRIES: u32 = 20;
+
+// Virtual shares and assets added to the share price. They make donating
+// yield into a near-empty vault cost far more than it can skim from the
+// rounding of later deposits.
+const VIRTUAL_SHARES: i128 = 1_000;
+const VIRTUAL_ASSETS: i128 = 1;
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum DataKey {
+    Admin,
+    Token,
+    UnbondingPeriod,        // Ledgers between unbonding and withdrawal
+    TotalShares,
+    TotalAssets,            // Bonded TRUE, including accrued yield
+    Shares(Address),
+    Delegation(Address),    // News source a staker backs
+    SourceShares(Symbol),   // Shares delegated to a news source
+    Unbonding(Address),
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct UnbondingEntry {
+    pub amount: i128,
+    pub release_ledger: u32,
+}
+
+#[contract]
+pub struct StakingVault;
+
+#[contractimpl]
+impl StakingVault {
+    // Initialize the vault for a token
+    pub fn initialize(env: Env, admin: Address, token: Address, unbonding_period: u32) {
+        // Ensure the contract is not already initialized
+        if env.storage().instance().has(&DataKey::Admin) {
+            panic!("Contract already initialized");
+        }
+
+        env.storage().instance().set(&DataKey::Admin, &admin);
+        env.storage().instance().set(&DataKey::Token, &token);
+        env.storage().instance().set(&DataKey::UnbondingPeriod, &unbonding_period);
+        env.storage().instance().set(&DataKey::TotalShares, &0i128);
+        env.storage().instance().set(&DataKey::TotalAssets, &0i128);
+    }
+
+    // Change the unbonding period for future unbonds (admin only)
+    pub fn set_unbonding_period(env: Env, admin: Address, unbonding_period: u32) {
+        // Verify the admin is calling
+        let stored_admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        if admin != stored_admin {
+            panic!("Only admin can set the unbonding period");
+        }
+        admin.require_auth();
+
+        env.storage().instance().set(&DataKey::UnbondingPeriod, &unbonding_period);
+    }
+
+    // Deposit TRUE and receive vault shares, optionally backing a news source
+    pub fn deposit(env: Env, from: Address, amount: i128, source: Option<Symbol>) -> i128 {
+        from.require_auth();
+
+        // Ensure amount is positive
+        if amount <= 0 {
+            panic!("Amount must be positive");
+        }
+
+        // Price shares against the current bonded assets
+        let total_shares: i128 = env.storage().instance().get(&DataKey::TotalShares).unwrap();
+        let total_assets: i128 = env.storage().instance().get(&DataKey::TotalAssets).unwrap();
+        let shares = amount * (total_shares + VIRTUAL_SHARES) / (total_assets + VIRTUAL_ASSETS);
+        if shares <= 0 {
+            panic!("Deposit is too small to mint shares");
+        }
+
+        // Pull the tokens into the vault
+        Self::token_client(&env).transfer(&from, &env.current_contract_address(), &amount);
+
+        // Move existing shares to the new source before adding the deposit
+        if let Some(source) = source {
+            Self::set_delegation(&env, &from, Some(source));
+        }
+
+        let user_shares = Self::shares_of(env.clone(), from.clone());
+        env.storage().persistent().set(&DataKey::Shares(from.clone()), &(user_shares + shares));
+        env.storage().instance().set(&DataKey::TotalShares, &(total_shares + shares));
+        env.storage().instance().set(&DataKey::TotalAssets, &(total_assets + amount));
+        Self::adjust_source_shares(&env, &from, shares);
+
+        env.events().publish((symbol_short!("deposit"), from), (amount, shares));
+
+        shares
+    }
+
+    // Change or clear the news source a staker's shares back
+    pub fn delegate(env: Env, from: Address, source: Option<Symbol>) {
+        from.require_auth();
+
+        Self::set_delegation(&env, &from, source.clone());
+
+        env.events().publish((symbol_short!("delegate"), from), source);
+    }
+
+    // Add TRUE to the vault without minting shares, raising every share's value.
+    // Meant for the stakers' portion of slashed verification stakes, which the
+    // verification contracts pay in through `settle_staker_yield`.
+    pub fn add_yield(env: Env, from: Address, amount: i128) {
+        from.require_auth();
+
+        // Ensure amount is positive
+        if amount <= 0 {
+            panic!("Amount must be positive");
+        }
+
+        let total_shares: i128 = env.storage().instance().get(&DataKey::TotalShares).unwrap();
+        if total_shares == 0 {
+            panic!("Cannot add yield to an empty vault");
+        }
+
+        Self::token_client(&env).transfer(&from, &env.current_contract_address(), &amount);
+
+        let total_assets: i128 = env.storage().instance().get(&DataKey::TotalAssets).unwrap();
+        env.storage().instance().set(&DataKey::TotalAssets, &(total_assets + amount));
+
+        env.events().publish((symbol_short!("yield"), from), amount);
+    }
+
+    // Burn shares and start the unbonding period for their value
+    pub fn unbond(env: Env, from: Address, shares: i128) -> i128 {
+        from.require_auth();
+
+        if shares <= 0 {
+            panic!("Shares must be positive");
+        }
+
+        let user_shares = Self::shares_of(env.clone(), from.clone());
+        if user_shares < shares {
+            panic!("Insufficient shares");
+        }
+
+        let mut unbonding = Self::get_unbonding(env.clone(), from.clone());
+        if unbonding.len() >= MAX_UNBONDING_ENTRIES {
+            panic!("Too many pending unbonding entries");
+        }
+
+        // Convert shares to assets at the current rate; unbonding stakes stop earning yield
+        let total_shares: i128 = env.storage().instance().get(&DataKey::TotalShares).unwrap();
+        let total_assets: i128 = env.storage().instance().get(&DataKey::TotalAssets).unwrap();
+        let amount = (shares * (total_assets + VIRTUAL_ASSETS) / (total_shares + VIRTUAL_SHARES)).min(total_assets);
+
+        Self::adjust_source_shares(&env, &from, -shares);
+        if user_shares == shares {
+            env.storage().persistent().remove(&DataKey::Shares(from.clone()));
+        } else {
+            env.storage().persistent().set(&DataKey::Shares(from.clone()), &(user_shares - shares));
+        }
+        env.storage().instance().set(&DataKey::TotalShares, &(total_shares - shares));
+        env.storage().instance().set(&DataKey::TotalAssets, &(total_assets - amount));
+
+        let unbonding_period: u32 = env.storage().instance().get(&DataKey::UnbondingPeriod).unwrap();
+        let release_ledger = env.ledger().sequence() + unbonding_period;
+        unbonding.push_back(UnbondingEntry {
+            amount,
+            release_ledger,
+        });
+        env.storage().persistent().set(&DataKey::Unbonding(from.clone()), &unbonding);
+
+        env.events().publish((symbol_short!("unbond"), from), (shares, amount, release_ledger));
+
+        amount
+    }
+
+    // Withdraw every unbonding entry whose period has passed
+    pub fn withdraw(env: Env, from: Address) -> i128 {
+        from.require_auth();
+
+        let now = env.ledger().sequence();
+        let mut pending = Vec::new(&env);
+        let mut amount = 0i128;
+
+        for entry in Self::get_unbonding(env.clone(), from.clone()).iter() {
+            if entry.release_ledger <= now {
+                amount += entry.amount;
+            } else {
+                pending.push_back(entry);
+            }
+        }
+
+        if amount == 0 {
+            panic!("Nothing to withdraw");
+        }
+
+        if pending.is_empty() {
+            env.storage().persistent().remove(&DataKey::Unbonding(from.clone()));
+        } else {
+            env.storage().persistent().set(&DataKey::Unbonding(from.clone()), &pending);
+        }
+
+        Self::token_client(&env).transfer(&env.current_contract_address(), &from, &amount);
+
+        env.events().publish((symbol_short!("withdraw"), from), amount);
+
+        amount
+    }
+
+    // Get the staked token
+    pub fn get_token(env: Env) -> Address {
+        env.storage().instance().get(&DataKey::Token).unwrap()
+    }
+
+    // Get the unbonding period in ledgers
+    pub fn get_unbonding_period(env: Env) -> u32 {
+        env.storage().instance().get(&DataKey::UnbondingPeriod).unwrap()
+    }
+
+    // Get a staker's shares
+    pub fn shares_of(env: Env, user: Address) -> i128 {
+        env.storage().persistent().get(&DataKey::Shares(user)).unwrap_or(0)
+    }
+
+    // Get the current TRUE value of a staker's shares
+    pub fn value_of(env: Env, user: Address) -> i128 {
+        let shares = Self::shares_of(env.clone(), user);
+        Self::shares_to_assets(&env, shares)
+    }
+
+    // Get total outstanding shares
+    pub fn total_shares(env: Env) -> i128 {
+        env.storage().instance().get(&DataKey::TotalShares).unwrap()
+    }
+
+    // Get total bonded TRUE, including accrued yield
+    pub fn total_assets(env: Env) -> i128 {
+        env.storage().instance().get(&DataKey::TotalAssets).unwrap()
+    }
+
+    // Get the news source a staker backs (if any)
+    pub fn get_delegation(env: Env, user: Address) -> Option<Symbol> {
+        env.storage().persistent().get(&DataKey::Delegation(user))
+    }
+
+    // Get the TRUE value staked in support of a news source
+    pub fn source_stake(env: Env, source: Symbol) -> i128 {
+        let shares: i128 = env.storage().persistent().get(&DataKey::SourceShares(source)).unwrap_or(0);
+        Self::shares_to_assets(&env, shares)
+    }
+
+    // Get a staker's pending unbonding entries
+    pub fn get_unbonding(env: Env, user: Address) -> Vec<UnbondingEntry> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Unbonding(user))
+            .unwrap_or_else(|| Vec::new(&env))
+    }
+
+    fn token_client(env: &Env) -> token::Client<'_> {
+        let token: Address = env.storage().instance().get(&DataKey::Token).unwrap();
+        token::Client::new(env, &token)
+    }
+
+    fn shares_to_assets(env: &Env, shares: i128) -> i128 {
+        let total_shares: i128 = env.storage().instance().get(&DataKey::TotalShares).unwrap();
+        let total_assets: i128 = env.storage().instance().get(&DataKey::TotalAssets).unwrap();
+        (shares * (total_assets + VIRTUAL_ASSETS) / (total_shares + VIRTUAL_SHARES)).min(total_assets)
+    }
+
+    // Point a staker at a new source, moving their shares between source totals
+    fn set_delegation(env: &Env, user: &Address, source: Option<Symbol>) {
+        let current: Option<Symbol> = env.storage().persistent().get(&DataKey::Delegation(user.clone()));
+        if current == source {
+            return;
+        }
+
+        let shares = Self::shares_of(env.clone(), user.clone());
+        Self::adjust_source_shares(env, user, -shares);
+
+        match source {
+            Some(source) => env.storage().persistent().set(&DataKey::Delegation(user.clone()), &source),
+            None => env.storage().persistent().remove(&DataKey::Delegation(user.clone())),
+        }
+
+        Self::adjust_source_shares(env, user, shares);
+    }
+
+    // Apply a share change to the staker's delegated source, if any
+    fn adjust_source_shares(env: &Env, user: &Address, delta: i128) {
+        if delta == 0 {
+            return;
+        }
+
+        let source: Option<Symbol> = env.storage().persistent().get(&DataKey::Delegation(user.clone()));
+        if let Some(source) = source {
+            let key = DataKey::SourceShares(source);
+            let shares: i128 = env.storage().persistent().get(&key).unwrap_or(0);
+            env.storage().persistent().set(&key, &(shares + delta));
+        }
+    }
+}
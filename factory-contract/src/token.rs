@@ -2,18 +2,106 @@
 // This implements a fungible token for rewarding news verification
 
 #![no_std]
-use soroban_sdk::{contract, contractimpl, contracttype, token, Address, Env, String};
+use soroban_sdk::{contract, contractimpl, contracttype, symbol_short, token::TokenInterface, xdr::ToXdr, Address, Bytes, BytesN, Env, String, Symbol, Vec};
 
 // Token metadata
 const DECIMAL_PLACES: u32 = 7;
 const NAME: &str = "TrueToken";
 const SYMBOL: &str = "TRUE";
 
+// Storage lifetimes, in ledgers (~5s each)
+const DAY_IN_LEDGERS: u32 = 17280;
+const INSTANCE_BUMP_AMOUNT: u32 = 7 * DAY_IN_LEDGERS;
+const INSTANCE_LIFETIME_THRESHOLD: u32 = INSTANCE_BUMP_AMOUNT - DAY_IN_LEDGERS;
+const BALANCE_BUMP_AMOUNT: u32 = 30 * DAY_IN_LEDGERS;
+const BALANCE_LIFETIME_THRESHOLD: u32 = BALANCE_BUMP_AMOUNT - DAY_IN_LEDGERS;
+
+// Supply cap and reward emission schedule
+const MAX_SUPPLY: i128 = 1_000_000_000 * 10_000_000; // 1B TRUE
+const EPOCH_LEDGERS: u32 = 7 * DAY_IN_LEDGERS; // One reward epoch per week
+const INITIAL_EPOCH_EMISSION: i128 = 1_000_000 * 10_000_000; // 1M TRUE per epoch
+const HALVING_EPOCHS: u32 = 52; // Per-epoch emission halves roughly every year
+
+// Domain tag for permit signatures, so they cannot be replayed as other messages
+const PERMIT_DOMAIN: &str = "TrueToken.permit.v1";
+
+// Bound on outstanding vesting locks per account, keeping claims cheap
+const MAX_VESTING_LOCKS: u32 = 50;
+
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub enum DataKey {
     TokenAdmin,
+    PendingAdmin,
     TokenMetadata,
+    Balance(Address),
+    Allowance(AllowanceDataKey),
+    TotalSupply,
+    EmissionStart,      // Ledger sequence at which epoch 0 began
+    EpochEmitted(u32),  // Rewards minted during an epoch
+    Minter(Address),    // Contracts allowed to call `mint_reward` without the admin
+    Frozen(Address),    // Accounts the admin has deauthorized
+    Vesting(Address),   // Outstanding reward locks per account
+    PermitKey(Address), // Ed25519 key allowed to sign permits for an account
+    PermitNonce(Address),
+    BalanceCheckpoint(Address, u32),
+    BalanceCheckpointCount(Address),
+    SupplyCheckpoint(u32),
+    SupplyCheckpointCount,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TokenMetadata {
+    pub decimal: u32,
+    pub name: String,
+    pub symbol: String,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AllowanceDataKey {
+    pub from: Address,
+    pub spender: Address,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AllowanceValue {
+    pub amount: i128,
+    pub expiration_ledger: u32,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MinterInfo {
+    pub remaining: i128,  // Rewards this minter may still mint
+    pub minted: i128,     // Rewards minted so far
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum VestingSchedule {
+    // Unlocks evenly from the start ledger to the end ledger
+    Linear(u32, u32),
+    // Unlocks entirely at the given ledger
+    Cliff(u32),
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct VestingLock {
+    pub amount: i128,
+    pub claimed: i128,
+    pub schedule: VestingSchedule,
+}
+
+// A value as of the end of a ledger
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Checkpoint {
+    pub ledger: u32,
+    pub value: i128,
 }
 
 #[contract]
@@ -23,139 +111,963 @@ pub struct TrueToken;
 impl TrueToken {
     // Initialize a new token contract
     pub fn initialize(env: Env, admin: Address) -> Address {
-        // Check if we've already initialized the token
-        if env.storage().instance().has(&DataKey::TokenAdmin) {
+        // Check if we've already initialized the token (the admin may since
+        // have been renounced, but the metadata is always present)
+        if env.storage().instance().has(&DataKey::TokenMetadata) {
             panic!("Token already initialized");
         }
 
         // Store the admin address
         env.storage().instance().set(&DataKey::TokenAdmin, &admin);
-        
-        // Initialize the token with the Soroban token interface
-        let token_client = token::Client::new(&env, &env.current_contract_address());
-        token_client.initialize(
-            &admin,
-            &DECIMAL_PLACES,
-            &String::from_str(&env, NAME),
-            &String::from_str(&env, SYMBOL),
-        );
-        
+
+        // Store the token metadata
+        let metadata = TokenMetadata {
+            decimal: DECIMAL_PLACES,
+            name: String::from_str(&env, NAME),
+            symbol: String::from_str(&env, SYMBOL),
+        };
+        env.storage().instance().set(&DataKey::TokenMetadata, &metadata);
+
+        // Start the emission schedule
+        env.storage().instance().set(&DataKey::TotalSupply, &0i128);
+        env.storage().instance().set(&DataKey::EmissionStart, &env.ledger().sequence());
+
         // Return the token contract's address
         env.current_contract_address()
     }
-    
+
     // Mint new tokens (only admin can call this)
     pub fn mint(env: Env, to: Address, amount: i128) {
         // Verify the admin is calling this function
-        let admin: Address = env.storage().instance().get(&DataKey::TokenAdmin).unwrap();
+        let admin = read_admin(&env);
         admin.require_auth();
-        
+
         // Check if amount is positive
         if amount <= 0 {
             panic!("Amount must be positive");
         }
-        
+
+        extend_instance(&env);
+
         // Mint tokens to the specified address
-        let token_client = token::Client::new(&env, &env.current_contract_address());
-        token_client.mint(&to, &amount);
+        require_authorized(&env, &to);
+        increase_supply(&env, amount);
+        receive_balance(&env, &to, amount);
+        env.events().publish((symbol_short!("mint"), admin, to), amount);
     }
-    
-    // Mint tokens to reward verifiers
-    pub fn mint_reward(env: Env, verifier: Address, amount: i128) {
-        // Verify the admin is calling this function
-        let admin: Address = env.storage().instance().get(&DataKey::TokenAdmin).unwrap();
-        admin.require_auth();
-        
+
+    // Mint tokens to reward verifiers. The minter is either the admin or a
+    // contract granted a minting allowance via `grant_minter`.
+    pub fn mint_reward(env: Env, minter: Address, verifier: Address, amount: i128) {
+        // Verify the minter is calling this function
+        minter.require_auth();
+
         // Check if amount is positive
         if amount <= 0 {
             panic!("Reward amount must be positive");
         }
-        
+
+        extend_instance(&env);
+
+        spend_reward_budget(&env, &minter, amount);
+
         // Mint reward tokens to the verifier
-        let token_client = token::Client::new(&env, &env.current_contract_address());
-        token_client.mint(&verifier, &amount);
+        require_authorized(&env, &verifier);
+        increase_supply(&env, amount);
+        receive_balance(&env, &verifier, amount);
+        env.events().publish((symbol_short!("mint"), minter, verifier), amount);
     }
-    
-    // Transfer tokens from one account to another (caller must be the 'from' account)
-    pub fn transfer(env: Env, from: Address, to: Address, amount: i128) {
-        // Require authorization from the sender
-        from.require_auth();
-        
+
+    // Mint a reward into a lock that releases to the verifier on `schedule`
+    pub fn mint_reward_vested(env: Env, minter: Address, verifier: Address, amount: i128, schedule: VestingSchedule) {
+        // Verify the minter is calling this function
+        minter.require_auth();
+
         // Check if amount is positive
         if amount <= 0 {
-            panic!("Amount must be positive");
+            panic!("Reward amount must be positive");
+        }
+
+        if let VestingSchedule::Linear(start_ledger, end_ledger) = schedule {
+            if end_ledger <= start_ledger {
+                panic!("Vesting must end after it starts");
+            }
+        }
+
+        extend_instance(&env);
+
+        spend_reward_budget(&env, &minter, amount);
+        require_authorized(&env, &verifier);
+
+        let mut locks = read_vesting_locks(&env, &verifier);
+        if let Some(index) = locks.iter().position(|lock| lock.schedule == schedule) {
+            // Top up the lock already on this schedule
+            let mut lock = locks.get(index as u32).unwrap();
+            lock.amount += amount;
+            locks.set(index as u32, lock);
+        } else {
+            // Pay out released rewards to make room before giving up
+            if locks.len() >= MAX_VESTING_LOCKS {
+                release_vested(&env, &verifier);
+                locks = read_vesting_locks(&env, &verifier);
+            }
+            if locks.len() >= MAX_VESTING_LOCKS {
+                panic!("Too many vesting locks for this account");
+            }
+            locks.push_back(VestingLock {
+                amount,
+                claimed: 0,
+                schedule,
+            });
         }
-        
-        // Transfer tokens
-        let token_client = token::Client::new(&env, &env.current_contract_address());
-        token_client.transfer(&from, &to, &amount);
+        write_vesting_locks(&env, &verifier, &locks);
+
+        // The contract holds locked rewards until they are claimed
+        let escrow = env.current_contract_address();
+        increase_supply(&env, amount);
+        receive_balance(&env, &escrow, amount);
+        env.events().publish((symbol_short!("mint"), minter, escrow), amount);
+        env.events().publish((symbol_short!("locked"), verifier), amount);
     }
-    
-    // Transfer tokens on behalf of another account (caller must have approval)
-    pub fn transfer_from(env: Env, spender: Address, from: Address, to: Address, amount: i128) {
-        // Require authorization from the spender
-        spender.require_auth();
-        
+
+    // Release an account's unlocked rewards to it, returning the amount claimed
+    pub fn claim_vested(env: Env, account: Address) -> i128 {
+        extend_instance(&env);
+
+        require_authorized(&env, &account);
+
+        release_vested(&env, &account)
+    }
+
+    // Get rewards still locked for an account
+    pub fn locked_balance(env: Env, account: Address) -> i128 {
+        let mut locked = 0i128;
+        for lock in read_vesting_locks(&env, &account).iter() {
+            locked += lock.amount - vested_amount(&env, &lock);
+        }
+        locked
+    }
+
+    // Get rewards that have unlocked but not yet been claimed
+    pub fn vested_balance(env: Env, account: Address) -> i128 {
+        let mut vested = 0i128;
+        for lock in read_vesting_locks(&env, &account).iter() {
+            vested += vested_amount(&env, &lock) - lock.claimed;
+        }
+        vested
+    }
+
+    // Get an account's outstanding vesting locks
+    pub fn get_vesting_locks(env: Env, account: Address) -> Vec<VestingLock> {
+        read_vesting_locks(&env, &account)
+    }
+
+    // Allow a contract to mint up to `allowance` rewards (only admin can call this)
+    pub fn grant_minter(env: Env, minter: Address, allowance: i128) {
+        // Verify the admin is calling this function
+        let admin = read_admin(&env);
+        admin.require_auth();
+
+        // Check if amount is positive
+        if allowance <= 0 {
+            panic!("Minter allowance must be positive");
+        }
+
+        extend_instance(&env);
+
+        // Re-granting resets the remaining allowance but keeps the minted total
+        let key = DataKey::Minter(minter.clone());
+        let minted = env
+            .storage()
+            .persistent()
+            .get::<DataKey, MinterInfo>(&key)
+            .map(|info| info.minted)
+            .unwrap_or(0);
+        let info = MinterInfo {
+            remaining: allowance,
+            minted,
+        };
+        env.storage().persistent().set(&key, &info);
+
+        env.events().publish((symbol_short!("minter"), symbol_short!("granted"), minter), allowance);
+    }
+
+    // Remove a contract's minting rights (only admin can call this)
+    pub fn revoke_minter(env: Env, minter: Address) {
+        // Verify the admin is calling this function
+        let admin = read_admin(&env);
+        admin.require_auth();
+
+        let key = DataKey::Minter(minter.clone());
+        if !env.storage().persistent().has(&key) {
+            panic!("Address is not a minter");
+        }
+        env.storage().persistent().remove(&key);
+
+        env.events().publish((symbol_short!("minter"), symbol_short!("revoked"), minter), ());
+    }
+
+    // Get a minter's remaining allowance and minted total (if it is a minter)
+    pub fn get_minter(env: Env, minter: Address) -> Option<MinterInfo> {
+        env.storage().persistent().get(&DataKey::Minter(minter))
+    }
+
+    // Freeze or unfreeze an account (only admin can call this). Frozen
+    // accounts cannot send, receive, burn or be minted rewards.
+    pub fn set_authorized(env: Env, id: Address, authorize: bool) {
+        // Verify the admin is calling this function
+        let admin = read_admin(&env);
+        admin.require_auth();
+
+        extend_instance(&env);
+
+        let key = DataKey::Frozen(id.clone());
+        if authorize {
+            env.storage().persistent().remove(&key);
+        } else {
+            env.storage().persistent().set(&key, &true);
+        }
+
+        env.events().publish((Symbol::new(&env, "set_authorized"), admin, id), authorize);
+    }
+
+    // Check whether an account is allowed to hold and move tokens
+    pub fn authorized(env: Env, id: Address) -> bool {
+        !env.storage().persistent().has(&DataKey::Frozen(id))
+    }
+
+    // Reclaim and destroy fraudulently earned tokens (only admin can call this)
+    pub fn clawback(env: Env, from: Address, amount: i128) {
+        // Verify the admin is calling this function
+        let admin = read_admin(&env);
+        admin.require_auth();
+
         // Check if amount is positive
         if amount <= 0 {
             panic!("Amount must be positive");
         }
-        
-        // Transfer tokens
-        let token_client = token::Client::new(&env, &env.current_contract_address());
-        token_client.transfer_from(&spender, &from, &to, &amount);
+
+        extend_instance(&env);
+
+        // Clawback works regardless of the account's frozen state
+        spend_balance(&env, &from, amount);
+        decrease_supply(&env, amount);
+        env.events().publish((symbol_short!("clawback"), admin, from), amount);
+    }
+
+    // Cancel an account's vesting locks and burn every unclaimed reward in
+    // them, e.g. after freezing a sybil farm (only admin can call this)
+    pub fn cancel_vesting(env: Env, account: Address) -> i128 {
+        // Verify the admin is calling this function
+        let admin = read_admin(&env);
+        admin.require_auth();
+
+        extend_instance(&env);
+
+        let mut unclaimed = 0i128;
+        for lock in read_vesting_locks(&env, &account).iter() {
+            unclaimed += lock.amount - lock.claimed;
+        }
+        if unclaimed == 0 {
+            return 0;
+        }
+
+        write_vesting_locks(&env, &account, &Vec::new(&env));
+
+        // The rewards were held in escrow by the contract
+        spend_balance(&env, &env.current_contract_address(), unclaimed);
+        decrease_supply(&env, unclaimed);
+        env.events().publish((symbol_short!("clawback"), admin, account), unclaimed);
+
+        unclaimed
     }
-    
-    // Approve a spender to transfer tokens on behalf of the owner
-    pub fn approve(env: Env, from: Address, spender: Address, amount: i128) {
+
+    // Register (or clear) the ed25519 key that may sign permits for `owner`
+    pub fn set_permit_key(env: Env, owner: Address, public_key: Option<BytesN<32>>) {
         // Require authorization from the owner
-        from.require_auth();
-        
+        owner.require_auth();
+
+        let key = DataKey::PermitKey(owner.clone());
+        match public_key.clone() {
+            Some(public_key) => {
+                env.storage().persistent().set(&key, &public_key);
+                env.storage()
+                    .persistent()
+                    .extend_ttl(&key, BALANCE_LIFETIME_THRESHOLD, BALANCE_BUMP_AMOUNT);
+            }
+            None => env.storage().persistent().remove(&key),
+        }
+
+        env.events().publish((Symbol::new(&env, "permit_key"), owner), public_key);
+    }
+
+    // Approve `spender` using a signature from the owner's permit key, so a
+    // relayer can submit the approval on the owner's behalf
+    pub fn permit(
+        env: Env,
+        owner: Address,
+        spender: Address,
+        amount: i128,
+        expiration_ledger: u32,
+        nonce: u64,
+        signature: BytesN<64>,
+    ) {
         // Check if amount is non-negative
         if amount < 0 {
             panic!("Amount must be non-negative");
         }
-        
-        // Approve the spender
-        let token_client = token::Client::new(&env, &env.current_contract_address());
-        token_client.approve(&from, &spender, &amount);
+
+        if env.ledger().sequence() > expiration_ledger {
+            panic!("Permit has expired");
+        }
+
+        let public_key: BytesN<32> = env
+            .storage()
+            .persistent()
+            .get(&DataKey::PermitKey(owner.clone()))
+            .unwrap_or_else(|| panic!("Owner has not registered a permit key"));
+
+        // Each nonce can be used exactly once, in order
+        let expected_nonce = read_permit_nonce(&env, &owner);
+        if nonce != expected_nonce {
+            panic!("Invalid permit nonce");
+        }
+
+        let digest = permit_digest(&env, &owner, &spender, amount, expiration_ledger, nonce);
+        env.crypto().ed25519_verify(&public_key, &Bytes::from(digest), &signature);
+
+        extend_instance(&env);
+
+        env.storage()
+            .persistent()
+            .set(&DataKey::PermitNonce(owner.clone()), &(nonce + 1));
+
+        write_allowance(&env, &owner, &spender, amount, expiration_ledger);
+        env.events().publish(
+            (symbol_short!("approve"), owner, spender),
+            (amount, expiration_ledger),
+        );
     }
-    
-    // Get the balance of an account
-    pub fn balance(env: Env, account: Address) -> i128 {
-        let token_client = token::Client::new(&env, &env.current_contract_address());
-        token_client.balance(&account)
+
+    // Get the next permit nonce for an account
+    pub fn permit_nonce(env: Env, owner: Address) -> u64 {
+        read_permit_nonce(&env, &owner)
+    }
+
+    // Get an account's registered permit key (if any)
+    pub fn permit_key(env: Env, owner: Address) -> Option<BytesN<32>> {
+        env.storage().persistent().get(&DataKey::PermitKey(owner))
+    }
+
+    // Get the digest a permit key must sign to authorize an approval
+    pub fn get_permit_digest(
+        env: Env,
+        owner: Address,
+        spender: Address,
+        amount: i128,
+        expiration_ledger: u32,
+        nonce: u64,
+    ) -> BytesN<32> {
+        permit_digest(&env, &owner, &spender, amount, expiration_ledger, nonce)
+    }
+
+    // Start handing the admin role to a new address (only admin can call this)
+    pub fn propose_admin(env: Env, new_admin: Address) {
+        // Verify the admin is calling this function
+        let admin = read_admin(&env);
+        admin.require_auth();
+
+        env.storage().instance().set(&DataKey::PendingAdmin, &new_admin);
+        env.events().publish((symbol_short!("admin"), symbol_short!("proposed")), (admin, new_admin));
+    }
+
+    // Complete the handover (only the proposed admin can call this)
+    pub fn accept_admin(env: Env) {
+        let new_admin: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::PendingAdmin)
+            .unwrap_or_else(|| panic!("No admin transfer is pending"));
+        new_admin.require_auth();
+
+        let old_admin = read_admin(&env);
+        env.storage().instance().set(&DataKey::TokenAdmin, &new_admin);
+        env.storage().instance().remove(&DataKey::PendingAdmin);
+        env.events().publish((symbol_short!("admin"), symbol_short!("accepted")), (old_admin, new_admin));
     }
-    
+
+    // Permanently give up the admin role (only admin can call this)
+    pub fn renounce_admin(env: Env) {
+        // Verify the admin is calling this function
+        let admin = read_admin(&env);
+        admin.require_auth();
+
+        env.storage().instance().remove(&DataKey::TokenAdmin);
+        env.storage().instance().remove(&DataKey::PendingAdmin);
+        env.events().publish((symbol_short!("admin"), symbol_short!("renounced")), admin);
+    }
+
     // Check if the specified account is the admin
     pub fn is_admin(env: Env, account: Address) -> bool {
-        let admin: Address = env.storage().instance().get(&DataKey::TokenAdmin).unwrap();
-        admin == account
+        let admin: Option<Address> = env.storage().instance().get(&DataKey::TokenAdmin);
+        admin == Some(account)
+    }
+
+    // Get the current admin (None once renounced)
+    pub fn get_admin(env: Env) -> Option<Address> {
+        env.storage().instance().get(&DataKey::TokenAdmin)
+    }
+
+    // Get the admin awaiting `accept_admin` (if any)
+    pub fn get_pending_admin(env: Env) -> Option<Address> {
+        env.storage().instance().get(&DataKey::PendingAdmin)
+    }
+
+    // Get the number of tokens in circulation
+    pub fn total_supply(env: Env) -> i128 {
+        read_total_supply(&env)
+    }
+
+    // Get an account's balance as of the end of `ledger`, a finished ledger
+    pub fn balance_at(env: Env, account: Address, ledger: u32) -> i128 {
+        read_checkpoint_at(
+            &env,
+            &DataKey::BalanceCheckpointCount(account.clone()),
+            |index| DataKey::BalanceCheckpoint(account.clone(), index),
+            ledger,
+        )
+    }
+
+    // Get several accounts' balances as of the end of `ledger`, in order
+    pub fn balances_at(env: Env, accounts: Vec<Address>, ledger: u32) -> Vec<i128> {
+        let mut balances = Vec::new(&env);
+        for account in accounts.iter() {
+            balances.push_back(Self::balance_at(env.clone(), account, ledger));
+        }
+        balances
+    }
+
+    // Get the total supply as of the end of `ledger`, a finished ledger
+    pub fn total_supply_at(env: Env, ledger: u32) -> i128 {
+        read_checkpoint_at(
+            &env,
+            &DataKey::SupplyCheckpointCount,
+            DataKey::SupplyCheckpoint,
+            ledger,
+        )
+    }
+
+    // Get the hard cap on total supply
+    pub fn max_supply(_env: Env) -> i128 {
+        MAX_SUPPLY
     }
-    
+
+    // Get the current reward epoch
+    pub fn current_epoch(env: Env) -> u32 {
+        current_epoch(&env)
+    }
+
+    // Get how much can still be minted via `mint_reward` in the current epoch
+    pub fn remaining_emission(env: Env) -> i128 {
+        let epoch = current_epoch(&env);
+        let remaining = epoch_emission(epoch) - read_epoch_emitted(&env, epoch);
+        let headroom = MAX_SUPPLY - read_total_supply(&env);
+        remaining.min(headroom).max(0)
+    }
+
+    // Get token metadata (name, symbol, decimals)
+    pub fn get_metadata(env: Env) -> (String, String, u32) {
+        let metadata = read_metadata(&env);
+        (metadata.name, metadata.symbol, metadata.decimal)
+    }
+}
+
+// Standard SEP-41 token interface, backed by this contract's own storage
+#[contractimpl]
+impl TokenInterface for TrueToken {
+    // Get the remaining allowance a spender has over an account's tokens
+    fn allowance(env: Env, from: Address, spender: Address) -> i128 {
+        extend_instance(&env);
+        read_allowance(&env, &from, &spender).amount
+    }
+
+    // Approve a spender to transfer tokens on behalf of the owner until `expiration_ledger`
+    fn approve(env: Env, from: Address, spender: Address, amount: i128, expiration_ledger: u32) {
+        // Require authorization from the owner
+        from.require_auth();
+
+        // Check if amount is non-negative
+        if amount < 0 {
+            panic!("Amount must be non-negative");
+        }
+
+        extend_instance(&env);
+
+        write_allowance(&env, &from, &spender, amount, expiration_ledger);
+        env.events().publish(
+            (symbol_short!("approve"), from, spender),
+            (amount, expiration_ledger),
+        );
+    }
+
+    // Get the balance of an account
+    fn balance(env: Env, id: Address) -> i128 {
+        extend_instance(&env);
+        read_balance(&env, &id)
+    }
+
+    // Transfer tokens from one account to another (caller must be the 'from' account)
+    fn transfer(env: Env, from: Address, to: Address, amount: i128) {
+        // Require authorization from the sender
+        from.require_auth();
+
+        // Check if amount is positive
+        if amount <= 0 {
+            panic!("Amount must be positive");
+        }
+
+        extend_instance(&env);
+
+        require_authorized(&env, &from);
+        require_authorized(&env, &to);
+        spend_balance(&env, &from, amount);
+        receive_balance(&env, &to, amount);
+        env.events().publish((symbol_short!("transfer"), from, to), amount);
+    }
+
+    // Transfer tokens on behalf of another account (caller must have approval)
+    fn transfer_from(env: Env, spender: Address, from: Address, to: Address, amount: i128) {
+        // Require authorization from the spender
+        spender.require_auth();
+
+        // Check if amount is positive
+        if amount <= 0 {
+            panic!("Amount must be positive");
+        }
+
+        extend_instance(&env);
+
+        require_authorized(&env, &from);
+        require_authorized(&env, &to);
+        spend_allowance(&env, &from, &spender, amount);
+        spend_balance(&env, &from, amount);
+        receive_balance(&env, &to, amount);
+        env.events().publish((symbol_short!("transfer"), from, to), amount);
+    }
+
     // Burn tokens (destroy them)
-    pub fn burn(env: Env, from: Address, amount: i128) {
+    fn burn(env: Env, from: Address, amount: i128) {
         // Require authorization from the sender
         from.require_auth();
-        
+
         // Check if amount is positive
         if amount <= 0 {
             panic!("Amount must be positive");
         }
-        
-        // Burn tokens
-        let token_client = token::Client::new(&env, &env.current_contract_address());
-        token_client.burn(&from, &amount);
+
+        extend_instance(&env);
+
+        require_authorized(&env, &from);
+        spend_balance(&env, &from, amount);
+        decrease_supply(&env, amount);
+        env.events().publish((symbol_short!("burn"), from), amount);
     }
-    
-    // Get token metadata (name, symbol, decimals)
-    pub fn get_metadata(env: Env) -> (String, String, u32) {
-        let token_client = token::Client::new(&env, &env.current_contract_address());
-        
-        let name = token_client.name();
-        let symbol = token_client.symbol();
-        let decimals = token_client.decimals();
-        
-        (name, symbol, decimals)
-    }
-} 
\ No newline at end of file
+
+    // Burn tokens on behalf of another account (caller must have approval)
+    fn burn_from(env: Env, spender: Address, from: Address, amount: i128) {
+        // Require authorization from the spender
+        spender.require_auth();
+
+        // Check if amount is positive
+        if amount <= 0 {
+            panic!("Amount must be positive");
+        }
+
+        extend_instance(&env);
+
+        require_authorized(&env, &from);
+        spend_allowance(&env, &from, &spender, amount);
+        spend_balance(&env, &from, amount);
+        decrease_supply(&env, amount);
+        env.events().publish((symbol_short!("burn"), from), amount);
+    }
+
+    // Get the number of decimals used to represent amounts
+    fn decimals(env: Env) -> u32 {
+        read_metadata(&env).decimal
+    }
+
+    // Get the token name
+    fn name(env: Env) -> String {
+        read_metadata(&env).name
+    }
+
+    // Get the token symbol
+    fn symbol(env: Env) -> String {
+        read_metadata(&env).symbol
+    }
+}
+
+// Keep the contract instance (admin, metadata) alive while it is in use
+fn extend_instance(env: &Env) {
+    env.storage()
+        .instance()
+        .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+}
+
+// Check the minter may mint `amount` rewards now and record the spend
+fn spend_reward_budget(env: &Env, minter: &Address, amount: i128) {
+    // Non-admin minters draw down their own allowance
+    let admin: Option<Address> = env.storage().instance().get(&DataKey::TokenAdmin);
+    if admin.as_ref() != Some(minter) {
+        let key = DataKey::Minter(minter.clone());
+        let mut info: MinterInfo = env
+            .storage()
+            .persistent()
+            .get(&key)
+            .unwrap_or_else(|| panic!("Caller is not an authorized minter"));
+        if info.remaining < amount {
+            panic!("Reward exceeds the minter allowance");
+        }
+        info.remaining -= amount;
+        info.minted += amount;
+        env.storage().persistent().set(&key, &info);
+    }
+
+    // Rewards are limited by the current epoch's emission budget
+    let epoch = current_epoch(env);
+    let emitted = read_epoch_emitted(env, epoch);
+    if emitted + amount > epoch_emission(epoch) {
+        panic!("Reward exceeds the epoch emission budget");
+    }
+    env.storage().persistent().set(&DataKey::EpochEmitted(epoch), &(emitted + amount));
+}
+
+fn require_authorized(env: &Env, id: &Address) {
+    if env.storage().persistent().has(&DataKey::Frozen(id.clone())) {
+        panic!("Account is frozen");
+    }
+}
+
+fn read_admin(env: &Env) -> Address {
+    env.storage()
+        .instance()
+        .get(&DataKey::TokenAdmin)
+        .unwrap_or_else(|| panic!("Token admin has been renounced"))
+}
+
+fn read_metadata(env: &Env) -> TokenMetadata {
+    env.storage().instance().get(&DataKey::TokenMetadata).unwrap()
+}
+
+fn read_balance(env: &Env, id: &Address) -> i128 {
+    let key = DataKey::Balance(id.clone());
+    if let Some(balance) = env.storage().persistent().get::<DataKey, i128>(&key) {
+        env.storage()
+            .persistent()
+            .extend_ttl(&key, BALANCE_LIFETIME_THRESHOLD, BALANCE_BUMP_AMOUNT);
+        balance
+    } else {
+        0
+    }
+}
+
+fn write_balance(env: &Env, id: &Address, amount: i128) {
+    let key = DataKey::Balance(id.clone());
+    env.storage().persistent().set(&key, &amount);
+    env.storage()
+        .persistent()
+        .extend_ttl(&key, BALANCE_LIFETIME_THRESHOLD, BALANCE_BUMP_AMOUNT);
+
+    write_checkpoint(
+        env,
+        &DataKey::BalanceCheckpointCount(id.clone()),
+        |index| DataKey::BalanceCheckpoint(id.clone(), index),
+        amount,
+    );
+}
+
+fn receive_balance(env: &Env, id: &Address, amount: i128) {
+    let balance = read_balance(env, id);
+    let new_balance = balance
+        .checked_add(amount)
+        .unwrap_or_else(|| panic!("Balance overflow"));
+    write_balance(env, id, new_balance);
+}
+
+fn spend_balance(env: &Env, id: &Address, amount: i128) {
+    let balance = read_balance(env, id);
+    if balance < amount {
+        panic!("Insufficient balance");
+    }
+    write_balance(env, id, balance - amount);
+}
+
+fn read_total_supply(env: &Env) -> i128 {
+    env.storage().instance().get(&DataKey::TotalSupply).unwrap_or(0)
+}
+
+fn increase_supply(env: &Env, amount: i128) {
+    let supply = read_total_supply(env) + amount;
+    if supply > MAX_SUPPLY {
+        panic!("Mint would exceed the maximum supply");
+    }
+    write_total_supply(env, supply);
+}
+
+fn decrease_supply(env: &Env, amount: i128) {
+    let supply = read_total_supply(env) - amount;
+    write_total_supply(env, supply);
+}
+
+fn write_total_supply(env: &Env, supply: i128) {
+    env.storage().instance().set(&DataKey::TotalSupply, &supply);
+    write_checkpoint(env, &DataKey::SupplyCheckpointCount, DataKey::SupplyCheckpoint, supply);
+}
+
+// Record `value` for the current ledger, replacing any earlier write in the same ledger
+fn write_checkpoint(env: &Env, count_key: &DataKey, entry_key: impl Fn(u32) -> DataKey, value: i128) {
+    let ledger = env.ledger().sequence();
+    let count: u32 = env.storage().persistent().get(count_key).unwrap_or(0);
+
+    let index = if count > 0 {
+        let last: Checkpoint = env.storage().persistent().get(&entry_key(count - 1)).unwrap();
+        if last.ledger == ledger {
+            count - 1
+        } else {
+            count
+        }
+    } else {
+        0
+    };
+
+    let key = entry_key(index);
+    env.storage().persistent().set(&key, &Checkpoint { ledger, value });
+    env.storage()
+        .persistent()
+        .extend_ttl(&key, BALANCE_LIFETIME_THRESHOLD, BALANCE_BUMP_AMOUNT);
+
+    if index == count {
+        env.storage().persistent().set(count_key, &(count + 1));
+    }
+    env.storage()
+        .persistent()
+        .extend_ttl(count_key, BALANCE_LIFETIME_THRESHOLD, BALANCE_BUMP_AMOUNT);
+}
+
+// Binary search for the last checkpoint at or before `ledger`
+// Only finished ledgers can be queried, so a value can't change after it is read
+fn read_checkpoint_at(env: &Env, count_key: &DataKey, entry_key: impl Fn(u32) -> DataKey, ledger: u32) -> i128 {
+    if ledger >= env.ledger().sequence() {
+        panic!("Ledger has not finished yet");
+    }
+
+    let count: u32 = env.storage().persistent().get(count_key).unwrap_or(0);
+    if count > 0 {
+        env.storage()
+            .persistent()
+            .extend_ttl(count_key, BALANCE_LIFETIME_THRESHOLD, BALANCE_BUMP_AMOUNT);
+    }
+
+    let mut low = 0u32;
+    let mut high = count;
+    while low < high {
+        let mid = low + (high - low) / 2;
+        if read_checkpoint(env, &entry_key(mid)).ledger <= ledger {
+            low = mid + 1;
+        } else {
+            high = mid;
+        }
+    }
+
+    // `low` is the number of checkpoints at or before `ledger`
+    if low == 0 {
+        0
+    } else {
+        read_checkpoint(env, &entry_key(low - 1)).value
+    }
+}
+
+// Read a checkpoint, keeping it alive for later historical queries
+fn read_checkpoint(env: &Env, key: &DataKey) -> Checkpoint {
+    let checkpoint: Checkpoint = env.storage().persistent().get(key).unwrap();
+    env.storage()
+        .persistent()
+        .extend_ttl(key, BALANCE_LIFETIME_THRESHOLD, BALANCE_BUMP_AMOUNT);
+    checkpoint
+}
+
+fn current_epoch(env: &Env) -> u32 {
+    let start: u32 = env.storage().instance().get(&DataKey::EmissionStart).unwrap();
+    (env.ledger().sequence() - start) / EPOCH_LEDGERS
+}
+
+// Per-epoch reward budget, halving every `HALVING_EPOCHS` epochs
+fn epoch_emission(epoch: u32) -> i128 {
+    let halvings = epoch / HALVING_EPOCHS;
+    if halvings >= 64 {
+        0
+    } else {
+        INITIAL_EPOCH_EMISSION >> halvings
+    }
+}
+
+fn read_epoch_emitted(env: &Env, epoch: u32) -> i128 {
+    env.storage()
+        .persistent()
+        .get(&DataKey::EpochEmitted(epoch))
+        .unwrap_or(0)
+}
+
+fn read_vesting_locks(env: &Env, account: &Address) -> Vec<VestingLock> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::Vesting(account.clone()))
+        .unwrap_or_else(|| Vec::new(env))
+}
+
+fn write_vesting_locks(env: &Env, account: &Address, locks: &Vec<VestingLock>) {
+    let key = DataKey::Vesting(account.clone());
+    if locks.is_empty() {
+        env.storage().persistent().remove(&key);
+    } else {
+        env.storage().persistent().set(&key, locks);
+        env.storage()
+            .persistent()
+            .extend_ttl(&key, BALANCE_LIFETIME_THRESHOLD, BALANCE_BUMP_AMOUNT);
+    }
+}
+
+// Pay out every released portion of an account's locks, dropping locks that
+// are fully released
+fn release_vested(env: &Env, account: &Address) -> i128 {
+    let locks = read_vesting_locks(env, account);
+    let mut remaining_locks = Vec::new(env);
+    let mut claimable = 0i128;
+
+    for mut lock in locks.iter() {
+        let unlocked = vested_amount(env, &lock);
+        claimable += unlocked - lock.claimed;
+        lock.claimed = unlocked;
+
+        // Drop locks that have been fully released
+        if lock.claimed < lock.amount {
+            remaining_locks.push_back(lock);
+        }
+    }
+
+    if claimable == 0 {
+        return 0;
+    }
+
+    write_vesting_locks(env, account, &remaining_locks);
+
+    let escrow = env.current_contract_address();
+    spend_balance(env, &escrow, claimable);
+    receive_balance(env, account, claimable);
+    env.events().publish((symbol_short!("transfer"), escrow, account.clone()), claimable);
+    env.events().publish((symbol_short!("claimed"), account.clone()), claimable);
+
+    claimable
+}
+
+// Portion of a lock released as of the current ledger
+fn vested_amount(env: &Env, lock: &VestingLock) -> i128 {
+    let now = env.ledger().sequence();
+    match lock.schedule {
+        VestingSchedule::Cliff(unlock_ledger) => {
+            if now >= unlock_ledger {
+                lock.amount
+            } else {
+                0
+            }
+        }
+        VestingSchedule::Linear(start_ledger, end_ledger) => {
+            if now <= start_ledger {
+                0
+            } else if now >= end_ledger {
+                lock.amount
+            } else {
+                lock.amount * (now - start_ledger) as i128 / (end_ledger - start_ledger) as i128
+            }
+        }
+    }
+}
+
+fn read_permit_nonce(env: &Env, owner: &Address) -> u64 {
+    env.storage()
+        .persistent()
+        .get(&DataKey::PermitNonce(owner.clone()))
+        .unwrap_or(0)
+}
+
+// sha256(domain || network id || token address || permit fields)
+fn permit_digest(
+    env: &Env,
+    owner: &Address,
+    spender: &Address,
+    amount: i128,
+    expiration_ledger: u32,
+    nonce: u64,
+) -> BytesN<32> {
+    let mut payload = Bytes::from_slice(env, PERMIT_DOMAIN.as_bytes());
+    payload.append(&Bytes::from(env.ledger().network_id()));
+    payload.append(&env.current_contract_address().to_xdr(env));
+    payload.append(&(owner.clone(), spender.clone(), amount, expiration_ledger, nonce).to_xdr(env));
+    env.crypto().sha256(&payload)
+}
+
+fn read_allowance(env: &Env, from: &Address, spender: &Address) -> AllowanceValue {
+    let key = DataKey::Allowance(AllowanceDataKey {
+        from: from.clone(),
+        spender: spender.clone(),
+    });
+    match env.storage().temporary().get::<DataKey, AllowanceValue>(&key) {
+        Some(allowance) if allowance.expiration_ledger >= env.ledger().sequence() => allowance,
+        Some(allowance) => AllowanceValue {
+            amount: 0,
+            expiration_ledger: allowance.expiration_ledger,
+        },
+        None => AllowanceValue {
+            amount: 0,
+            expiration_ledger: 0,
+        },
+    }
+}
+
+fn write_allowance(env: &Env, from: &Address, spender: &Address, amount: i128, expiration_ledger: u32) {
+    let current_ledger = env.ledger().sequence();
+    if amount > 0 && expiration_ledger < current_ledger {
+        panic!("Expiration ledger is in the past");
+    }
+
+    let key = DataKey::Allowance(AllowanceDataKey {
+        from: from.clone(),
+        spender: spender.clone(),
+    });
+    let allowance = AllowanceValue {
+        amount,
+        expiration_ledger,
+    };
+    env.storage().temporary().set(&key, &allowance);
+
+    // Keep the allowance entry alive until it expires
+    if amount > 0 {
+        let live_for = expiration_ledger - current_ledger;
+        env.storage().temporary().extend_ttl(&key, live_for, live_for);
+    }
+}
+
+fn spend_allowance(env: &Env, from: &Address, spender: &Address, amount: i128) {
+    let allowance = read_allowance(env, from, spender);
+    if allowance.amount < amount {
+        panic!("Insufficient allowance");
+    }
+    write_allowance(
+        env,
+        from,
+        spender,
+        allowance.amount - amount,
+        allowance.expiration_ledger,
+    );
+}
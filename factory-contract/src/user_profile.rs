@@ -2,40 +2,266 @@
 // This contract manages user profiles, reputation, and verification history
 
 #![no_std]
-use soroban_sdk::{contract, contractimpl, contracttype, Address, BytesN, Env, Map, Vec, Symbol};
+use soroban_sdk::{contract, contractimpl, contracttype, symbol_short, xdr::ToXdr, Address, BytesN, Env, Map, Vec, Symbol};
+
+use crate::achievements::{Achievement, AchievementBadgesClient};
+
+// Usernames are 3-32 characters (symbols are capped at 32)
+const MIN_USERNAME_LENGTH: usize = 3;
+const MAX_USERNAME_LENGTH: usize = 32;
+
+// Reputation bounds
+const MIN_REPUTATION: u32 = 1;
+const MAX_REPUTATION: u32 = 1000;
+
+// Entries kept per leaderboard
+const LEADERBOARD_SIZE: u32 = 50;
+
+// Leaderboard epoch lengths, in seconds
+const WEEK_SECONDS: u64 = 7 * 24 * 60 * 60;
+const MONTH_SECONDS: u64 = 30 * 24 * 60 * 60;
+
+// Most verification records returned by one history query
+const MAX_HISTORY_PAGE: u32 = 100;
+
+// History entries are kept alive for 30 days past their last write or read
+const DAY_IN_LEDGERS: u32 = 17280;
+const HISTORY_BUMP_AMOUNT: u32 = 30 * DAY_IN_LEDGERS;
+const HISTORY_LIFETIME_THRESHOLD: u32 = HISTORY_BUMP_AMOUNT - DAY_IN_LEDGERS;
+
+// Weekly and monthly leaderboard data lives in temporary storage and expires
+// one epoch (plus a day) after its last write, so at most the two epochs
+// before the current one can still hold an address
+const WEEK_LEDGERS: u32 = 7 * DAY_IN_LEDGERS;
+const MONTH_LEDGERS: u32 = 30 * DAY_IN_LEDGERS;
+const LIVE_PAST_EPOCHS: u64 = 2;
+
+// Achievement milestones
+const ACCURATE_MILESTONE: u32 = 100;
+const STREAK_MILESTONE: u32 = 10;
+
+// Attestations one profile can hold
+const MAX_ATTESTATIONS: u32 = 20;
+
+// Follow and delegation bounds
+const MAX_FOLLOWING: u32 = 100;
+const MAX_FOLLOWERS: u32 = 100;
+const MAX_DELEGATORS: u32 = 100;
+pub const MAX_DELEGATION_DEPTH: u32 = 3;  // Hops a delegated vote can travel
+pub const MAX_DELEGATED_VOTES: u32 = 25;  // Delegators counted toward one voter
+pub const MAX_TALLY_DELEGATED_VOTES: u32 = 100;  // Delegators counted across one tally
+
+// Names no user can claim, seeded at initialization
+const DEFAULT_RESERVED_USERNAMES: [&str; 7] = ["admin", "truelens", "support", "moderator", "official", "system", "root"];
 
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub enum DataKey {
     Admin,
+    PendingAdmin,
     Profiles,             // Map of user address to profile
-    VerificationHistory,  // Map of user address to verification history
+    HistoryEntry(Address, u32),  // A user's verification record by index
     Level,                // Map of user address to level
     Reputation,           // Map of user address to reputation score
+    ReputationUpdated,    // Map of user address to when reputation was last written
+    ReputationParams,
+    LevelTiers,           // Ordered level requirements
+    Leaderboard(LeaderboardKind, LeaderboardPeriod, u64),  // Top entries per metric, period and epoch
+    EpochRewards(Address, LeaderboardPeriod, u64),         // Rewards earned by a user within an epoch (temporary)
+    Username(Symbol),     // Normalized username to owning address
+    ReservedUsernames,    // Normalized usernames nobody can claim
+    Attestor(Address),    // Registered attestor and its weight
+    Attestations(Address),  // Attestations held by a user
+    BadgeContract,        // Achievement badge contract this contract mints from
+    DeletedTotals,        // Anonymized counters carried over from deleted profiles
+    PendingPurge(Address),  // History entries of a deleted profile still to remove
+    DeletedReputation(BytesN<32>),  // Reputation retained after deletion, keyed by address hash (linkable)
+    Following(Address),   // Profiles a user follows
+    Followers(Address),   // Profiles following a user
+    Delegation(Address),  // A user's outgoing delegation
+    Delegators(Address),  // Users delegating directly to an address
+    DelegationHeight(Address),  // Hops in the longest delegation chain ending at an address
 }
 
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct UserProfile {
-    address: Address,
-    username: Symbol,
-    bio: Symbol,
-    join_timestamp: u64,
-    verification_count: u32,
-    accuracy_percentage: u32,  // 0-100%
-    rewards_earned: i128,
-    level: u32,
+    pub address: Address,
+    pub username: Symbol,
+    pub bio: Symbol,
+    pub join_timestamp: u64,
+    pub verification_count: u32,   // Also the number of history entries
+    pub accurate_count: u32,       // Verifications that matched consensus
+    pub accuracy_percentage: u32,  // 0-100%
+    pub rewards_earned: i128,
+    pub level: u32,
+    pub current_streak: u32,   // Consecutive verifications matching consensus
+    pub longest_streak: u32,
 }
 
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct VerificationRecord {
-    news_id: BytesN<32>,
-    verdict: Symbol,        // "verify" or "flag"
-    stake_amount: i128,
-    timestamp: u64,
-    consensus_match: bool,  // Whether the user's verdict matched consensus
-    reward_amount: i128,
+    pub news_id: BytesN<32>,
+    pub verdict: Symbol,        // "verify" or "flag"
+    pub stake_amount: i128,
+    pub timestamp: u64,
+    pub consensus_match: bool,  // Whether the user's verdict matched consensus
+    pub reward_amount: i128,
+}
+
+// Kinds of evidence that a profile belongs to a unique human
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum AttestationKind {
+    Kyc,
+    DeviceAttestation,  // e.g. passkey device attestation
+    SocialProof,
+}
+
+// A registered attestor and the weight each of its attestations carries
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Attestor {
+    pub kind: AttestationKind,
+    pub weight: u32,
+}
+
+// An attestor's claim about a profile. Weight is read from the attestor's
+// current registration, so removing an attestor voids its attestations.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Attestation {
+    pub attestor: Address,
+    pub kind: AttestationKind,
+    pub issued_at: u64,
+    pub expires_at: Option<u64>,
+}
+
+// Voting power a user lends to another profile. `NewsVerification` counts up
+// to `max_stake` toward the delegate's verdict when the user does not vote.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Delegation {
+    pub delegate: Address,
+    pub max_stake: i128,
+}
+
+// A delegator whose voting power reaches a voter, for tallying
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DelegatedVote {
+    pub delegator: Address,
+    pub max_stake: i128,
+    pub attestation_weight: u32,
+}
+
+// Snapshot of a user's standing, for cross-contract reputation queries
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ReputationSummary {
+    pub user: Address,
+    pub reputation: u32,
+    pub level: u32,
+    pub accuracy_percentage: u32,
+    pub verification_count: u32,
+    pub rewards_earned: i128,
+}
+
+// Admin-configurable reputation model. Scores decay exponentially toward
+// `neutral` while a user is inactive, and are computed lazily on read.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ReputationParams {
+    pub neutral: u32,       // Score new and long-inactive users converge to
+    pub half_life: u64,     // Seconds for the distance from neutral to halve (0 disables decay)
+    pub reward: u32,        // Gain for matching consensus
+    pub penalty: u32,       // Base loss for missing consensus
+    pub stake_unit: i128,   // Stake at which the penalty doubles
+}
+
+// Requirements for a level; a user holds the highest tier whose
+// requirements they currently meet
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LevelTier {
+    pub level: u32,
+    pub min_verifications: u32,
+    pub min_accuracy: u32,    // 0-100%
+    pub min_reputation: u32,
+    pub perks: Vec<Symbol>,
+}
+
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum LeaderboardKind {
+    Reputation,
+    Accuracy,
+    RewardsEarned,
+    Level,
+}
+
+// Epoch leaderboards start empty each week or month; for `RewardsEarned`
+// they rank rewards earned within the epoch
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum LeaderboardPeriod {
+    AllTime,
+    Weekly,
+    Monthly,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LeaderboardEntry {
+    pub user: Address,
+    pub score: i128,
+}
+
+// Counters from deleted profiles, kept without any link to the addresses
+// so platform-wide totals stay consistent
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DeletedTotals {
+    pub profiles: u32,
+    pub verification_count: u32,
+    pub accurate_count: u32,
+    pub rewards_earned: i128,
+}
+
+// Everything stored about an address, as returned by `export_profile`
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ProfileExport {
+    pub profile: UserProfile,
+    pub reputation: u32,
+    pub level: u32,
+    pub history: Vec<VerificationRecord>,
+    pub attestations: Vec<Attestation>,
+    pub following: Vec<Address>,
+    pub delegation: Option<Delegation>,
+    pub epoch_rewards: Vec<EpochReward>,
+    pub standings: Vec<LeaderboardStanding>,
+    pub badges: Vec<u32>,       // Badge token ids held in the linked badge contract
+}
+
+// Rewards a user earned within a weekly or monthly epoch
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct EpochReward {
+    pub period: LeaderboardPeriod,
+    pub epoch: u64,
+    pub amount: i128,
+}
+
+// A user's score on one stored leaderboard
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LeaderboardStanding {
+    pub kind: LeaderboardKind,
+    pub period: LeaderboardPeriod,
+    pub epoch: u64,
+    pub score: i128,
 }
 
 #[contract]
@@ -46,7 +272,7 @@ impl UserProfileContract {
     // Initialize the contract
     pub fn initialize(env: Env, admin: Address) {
         // Ensure the contract is not already initialized
-        if env.storage().instance().has(&DataKey::Admin) {
+        if env.storage().instance().has(&DataKey::Profiles) {
             panic!("Contract already initialized");
         }
         
@@ -55,9 +281,99 @@ impl UserProfileContract {
         
         // Initialize empty collections
         env.storage().instance().set(&DataKey::Profiles, &Map::<Address, UserProfile>::new(&env));
-        env.storage().instance().set(&DataKey::VerificationHistory, &Map::<Address, Vec<VerificationRecord>>::new(&env));
         env.storage().instance().set(&DataKey::Level, &Map::<Address, u32>::new(&env));
         env.storage().instance().set(&DataKey::Reputation, &Map::<Address, u32>::new(&env));
+        
+        env.storage().instance().set(&DataKey::ReputationUpdated, &Map::<Address, u64>::new(&env));
+        
+        // Default reputation model: neutral 100, 90-day half-life
+        let params = ReputationParams {
+            neutral: 100,
+            half_life: 90 * 24 * 60 * 60,
+            reward: 20,
+            penalty: 30,
+            stake_unit: 100_0000000,
+        };
+        env.storage().instance().set(&DataKey::ReputationParams, &params);
+        
+        // Default level tiers
+        let mut tiers = Vec::<LevelTier>::new(&env);
+        for (level, min_verifications, min_accuracy, min_reputation) in [(1, 0, 0, 0), (2, 10, 60, 600), (3, 25, 70, 700), (4, 50, 80, 800), (5, 100, 90, 900)] {
+            tiers.push_back(LevelTier {
+                level,
+                min_verifications,
+                min_accuracy,
+                min_reputation,
+                perks: Vec::new(&env),
+            });
+        }
+        env.storage().instance().set(&DataKey::LevelTiers, &tiers);
+        
+        // Seed the reserved username blocklist
+        let mut reserved = Vec::<Symbol>::new(&env);
+        for name in DEFAULT_RESERVED_USERNAMES.iter() {
+            reserved.push_back(Symbol::new(&env, name));
+        }
+        env.storage().instance().set(&DataKey::ReservedUsernames, &reserved);
+    }
+    
+    // Propose a new admin; takes effect once they call `accept_admin`
+    pub fn propose_admin(env: Env, admin: Address, new_admin: Address) {
+        // Verify the admin is calling
+        let stored_admin = Self::read_admin(&env);
+        if admin != stored_admin {
+            panic!("Only admin can propose a new admin");
+        }
+        admin.require_auth();
+        
+        env.storage().instance().set(&DataKey::PendingAdmin, &new_admin);
+        env.events().publish((symbol_short!("admin"), symbol_short!("proposed")), (admin, new_admin));
+    }
+    
+    // Complete the handover (only the proposed admin can call this)
+    pub fn accept_admin(env: Env) {
+        let new_admin: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::PendingAdmin)
+            .unwrap_or_else(|| panic!("No admin transfer is pending"));
+        new_admin.require_auth();
+        
+        let old_admin = Self::read_admin(&env);
+        env.storage().instance().set(&DataKey::Admin, &new_admin);
+        env.storage().instance().remove(&DataKey::PendingAdmin);
+        env.events().publish((symbol_short!("admin"), symbol_short!("accepted")), (old_admin, new_admin));
+    }
+    
+    // Permanently give up the admin role (admin only)
+    pub fn renounce_admin(env: Env, admin: Address) {
+        // Verify the admin is calling
+        let stored_admin = Self::read_admin(&env);
+        if admin != stored_admin {
+            panic!("Only admin can renounce the admin role");
+        }
+        admin.require_auth();
+        
+        env.storage().instance().remove(&DataKey::Admin);
+        env.storage().instance().remove(&DataKey::PendingAdmin);
+        env.events().publish((symbol_short!("admin"), symbol_short!("renounced")), admin);
+    }
+    
+    // Get the current admin (None once renounced)
+    pub fn get_admin(env: Env) -> Option<Address> {
+        env.storage().instance().get(&DataKey::Admin)
+    }
+    
+    fn read_admin(env: &Env) -> Address {
+        env.storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .unwrap_or_else(|| panic!("Admin role has been renounced"))
+    }
+    
+    // Get the admin awaiting `accept_admin` (if any)
+    pub fn get_pending_admin(env: Env) -> Option<Address> {
+        env.storage().instance().get(&DataKey::PendingAdmin)
     }
     
     // Create a new user profile
@@ -72,6 +388,13 @@ impl UserProfileContract {
         if profiles.contains_key(&user) {
             panic!("User already has a profile");
         }
+        if env.storage().persistent().has(&DataKey::PendingPurge(user.clone())) {
+            panic!("Deleted profile history is still being removed");
+        }
+        
+        // Claim the username
+        let normalized = Self::normalize_username(&env, &username);
+        Self::claim_username(&env, &normalized, &user);
         
         // Create the profile
         let profile = UserProfile {
@@ -80,28 +403,30 @@ impl UserProfileContract {
             bio: Symbol::from_str(&env, ""),
             join_timestamp: env.ledger().timestamp(),
             verification_count: 0,
+            accurate_count: 0,
             accuracy_percentage: 0,
             rewards_earned: 0,
             level: 1,
+            current_streak: 0,
+            longest_streak: 0,
         };
         
         // Store the profile
         profiles.set(user.clone(), profile);
         env.storage().instance().set(&DataKey::Profiles, &profiles);
         
-        // Initialize verification history
-        let mut history_map: Map<Address, Vec<VerificationRecord>> = env.storage().instance().get(&DataKey::VerificationHistory).unwrap();
-        history_map.set(user.clone(), Vec::<VerificationRecord>::new(&env));
-        env.storage().instance().set(&DataKey::VerificationHistory, &history_map);
-        
         // Initialize level and reputation
         let mut level_map: Map<Address, u32> = env.storage().instance().get(&DataKey::Level).unwrap();
         level_map.set(user.clone(), 1);
         env.storage().instance().set(&DataKey::Level, &level_map);
         
-        let mut reputation_map: Map<Address, u32> = env.storage().instance().get(&DataKey::Reputation).unwrap();
-        reputation_map.set(user.clone(), 100); // Start with neutral reputation
-        env.storage().instance().set(&DataKey::Reputation, &reputation_map);
+        // Start with neutral reputation, or the capped score of a deleted profile
+        let params = Self::get_reputation_params(env.clone());
+        let carried: Option<u32> = env.storage().persistent().get(&DataKey::DeletedReputation(Self::address_hash(&env, &user)));
+        Self::write_reputation(&env, &user, carried.unwrap_or(params.neutral));
+        
+        // Rank the new profile straight away rather than after its first verdict
+        Self::update_leaderboards(&env, &user, 0);
     }
     
     // Update user profile
@@ -122,6 +447,13 @@ impl UserProfileContract {
         
         // Update fields if provided
         if let Some(new_username) = username {
+            // Claim the new name and release the old one, unless only the casing changed
+            let old_normalized = Self::normalize_username(&env, &profile.username);
+            let new_normalized = Self::normalize_username(&env, &new_username);
+            if new_normalized != old_normalized {
+                Self::claim_username(&env, &new_normalized, &user);
+                env.storage().persistent().remove(&DataKey::Username(old_normalized));
+            }
             profile.username = new_username;
         }
         
@@ -134,6 +466,497 @@ impl UserProfileContract {
         env.storage().instance().set(&DataKey::Profiles, &profiles);
     }
     
+    // Delete a user's profile: username, bio, history, reputation, attestations
+    // and leaderboard entries. Verification counts and rewards are folded into
+    // anonymized totals. Badges are soulbound and stay in the badge contract.
+    // History beyond the first page is removed by `purge_history`.
+    //
+    // Retention: to stop users escaping penalties by re-registering, the
+    // reputation (capped at neutral) is kept indefinitely under the SHA-256 of
+    // the address. Anyone can hash a known address to find it, so this is
+    // pseudonymous data linked to the address, not anonymized.
+    pub fn delete_profile(env: Env, user: Address) {
+        // Verify that the user is calling
+        user.require_auth();
+        
+        let mut profiles: Map<Address, UserProfile> = env.storage().instance().get(&DataKey::Profiles).unwrap();
+        let profile = profiles.get(user.clone()).unwrap_or_else(|| panic!("User does not have a profile"));
+        
+        // Keep the aggregate counters
+        let mut totals = Self::get_deleted_totals(env.clone());
+        totals.profiles += 1;
+        totals.verification_count += profile.verification_count;
+        totals.accurate_count += profile.accurate_count;
+        totals.rewards_earned += profile.rewards_earned;
+        env.storage().instance().set(&DataKey::DeletedTotals, &totals);
+        
+        // Retain a reputation below neutral for any future profile at this address
+        let neutral = Self::get_reputation_params(env.clone()).neutral;
+        let reputation = Self::get_reputation(env.clone(), user.clone()).min(neutral);
+        env.storage().persistent().set(&DataKey::DeletedReputation(Self::address_hash(&env, &user)), &reputation);
+        
+        // Release the username and drop the profile
+        let normalized = Self::normalize_username(&env, &profile.username);
+        env.storage().persistent().remove(&DataKey::Username(normalized));
+        profiles.remove(user.clone());
+        env.storage().instance().set(&DataKey::Profiles, &profiles);
+        
+        // Remove the first page of verification history
+        if profile.verification_count > 0 {
+            env.storage().persistent().set(&DataKey::PendingPurge(user.clone()), &profile.verification_count);
+            Self::purge_history_page(&env, &user);
+        }
+        
+        // Remove level and reputation
+        let mut level_map: Map<Address, u32> = env.storage().instance().get(&DataKey::Level).unwrap();
+        level_map.remove(user.clone());
+        env.storage().instance().set(&DataKey::Level, &level_map);
+        
+        let mut reputation_map: Map<Address, u32> = env.storage().instance().get(&DataKey::Reputation).unwrap();
+        reputation_map.remove(user.clone());
+        env.storage().instance().set(&DataKey::Reputation, &reputation_map);
+        
+        let mut updated_map: Map<Address, u64> = env.storage().instance().get(&DataKey::ReputationUpdated).unwrap_or_else(|| Map::new(&env));
+        updated_map.remove(user.clone());
+        env.storage().instance().set(&DataKey::ReputationUpdated, &updated_map);
+        
+        // Remove attestations
+        env.storage().persistent().remove(&DataKey::Attestations(user.clone()));
+        
+        // Remove follows and delegations in both directions
+        for target in Self::get_following(env.clone(), user.clone()).iter() {
+            Self::remove_edge(&env, &DataKey::Followers(target), &user);
+        }
+        for follower in Self::get_followers(env.clone(), user.clone()).iter() {
+            Self::remove_edge(&env, &DataKey::Following(follower), &user);
+        }
+        env.storage().persistent().remove(&DataKey::Following(user.clone()));
+        env.storage().persistent().remove(&DataKey::Followers(user.clone()));
+        
+        Self::remove_delegation(&env, &user);
+        for delegator in Self::get_delegators(env.clone(), user.clone()).iter() {
+            env.storage().persistent().remove(&DataKey::Delegation(delegator));
+        }
+        env.storage().persistent().remove(&DataKey::Delegators(user.clone()));
+        env.storage().persistent().remove(&DataKey::DelegationHeight(user.clone()));
+        
+        // Remove epoch rewards and leaderboard entries from every epoch still stored
+        for period in [LeaderboardPeriod::AllTime, LeaderboardPeriod::Weekly, LeaderboardPeriod::Monthly] {
+            for epoch in Self::live_epochs(&env, period) {
+                if period != LeaderboardPeriod::AllTime {
+                    env.storage().temporary().remove(&DataKey::EpochRewards(user.clone(), period, epoch));
+                }
+                for kind in [LeaderboardKind::Reputation, LeaderboardKind::Accuracy, LeaderboardKind::RewardsEarned, LeaderboardKind::Level] {
+                    Self::remove_from_leaderboard(&env, kind, period, epoch, &user);
+                }
+            }
+        }
+        
+        env.events().publish((symbol_short!("profile"), symbol_short!("deleted")), user);
+    }
+    
+    // Get everything stored about a user in one value. History is paged like
+    // `get_verification_history`; `profile.verification_count` is its length.
+    pub fn export_profile(env: Env, user: Address, history_start: u32, history_limit: u32) -> ProfileExport {
+        let profile = Self::get_profile(env.clone(), user.clone());
+        
+        let badges = match Self::get_badge_contract(env.clone()) {
+            Some(badges) => AchievementBadgesClient::new(&env, &badges).get_badges(&user),
+            None => Vec::new(&env),
+        };
+        
+        // Collect rewards and scores from every epoch still stored
+        let mut epoch_rewards = Vec::new(&env);
+        let mut standings = Vec::new(&env);
+        for period in [LeaderboardPeriod::AllTime, LeaderboardPeriod::Weekly, LeaderboardPeriod::Monthly] {
+            for epoch in Self::live_epochs(&env, period) {
+                if period != LeaderboardPeriod::AllTime {
+                    let amount = Self::read_epoch_rewards(&env, &user, period, epoch);
+                    if amount != 0 {
+                        epoch_rewards.push_back(EpochReward { period, epoch, amount });
+                    }
+                }
+                for kind in [LeaderboardKind::Reputation, LeaderboardKind::Accuracy, LeaderboardKind::RewardsEarned, LeaderboardKind::Level] {
+                    for entry in Self::read_leaderboard(&env, kind, period, epoch).iter() {
+                        if entry.user == user {
+                            standings.push_back(LeaderboardStanding { kind, period, epoch, score: entry.score });
+                        }
+                    }
+                }
+            }
+        }
+        
+        ProfileExport {
+            reputation: Self::get_reputation(env.clone(), user.clone()),
+            level: Self::get_level(env.clone(), user.clone()),
+            history: Self::get_verification_history(env.clone(), user.clone(), history_start, history_limit),
+            attestations: Self::get_attestations(env.clone(), user.clone()),
+            following: Self::get_following(env.clone(), user.clone()),
+            delegation: Self::get_delegation(env.clone(), user.clone()),
+            epoch_rewards,
+            standings,
+            badges,
+            profile,
+        }
+    }
+    
+    // Follow another profile
+    pub fn follow(env: Env, user: Address, target: Address) {
+        user.require_auth();
+        
+        if user == target {
+            panic!("Cannot follow yourself");
+        }
+        if !Self::has_profile(env.clone(), user.clone()) || !Self::has_profile(env.clone(), target.clone()) {
+            panic!("User does not have a profile");
+        }
+        
+        let mut following = Self::get_following(env.clone(), user.clone());
+        if following.contains(&target) {
+            panic!("Already following");
+        }
+        if following.len() >= MAX_FOLLOWING {
+            panic!("Following too many profiles");
+        }
+        let mut followers = Self::get_followers(env.clone(), target.clone());
+        if followers.len() >= MAX_FOLLOWERS {
+            panic!("Profile has too many followers");
+        }
+        following.push_back(target.clone());
+        env.storage().persistent().set(&DataKey::Following(user.clone()), &following);
+        followers.push_back(user.clone());
+        env.storage().persistent().set(&DataKey::Followers(target.clone()), &followers);
+        
+        env.events().publish((symbol_short!("follow"), user), target);
+    }
+    
+    // Stop following a profile
+    pub fn unfollow(env: Env, user: Address, target: Address) {
+        user.require_auth();
+        
+        let mut following = Self::get_following(env.clone(), user.clone());
+        let index = following.first_index_of(&target).unwrap_or_else(|| panic!("Not following"));
+        following.remove(index);
+        env.storage().persistent().set(&DataKey::Following(user.clone()), &following);
+        Self::remove_edge(&env, &DataKey::Followers(target.clone()), &user);
+        
+        env.events().publish((symbol_short!("unfollow"), user), target);
+    }
+    
+    // Get the profiles a user follows
+    pub fn get_following(env: Env, user: Address) -> Vec<Address> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Following(user))
+            .unwrap_or_else(|| Vec::new(&env))
+    }
+    
+    // Get the profiles following a user
+    pub fn get_followers(env: Env, user: Address) -> Vec<Address> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Followers(user))
+            .unwrap_or_else(|| Vec::new(&env))
+    }
+    
+    // Get how many profiles follow a user
+    pub fn get_follower_count(env: Env, user: Address) -> u32 {
+        Self::get_followers(env, user).len()
+    }
+    
+    // Drop an address from a stored follow list, if it is there
+    fn remove_edge(env: &Env, key: &DataKey, user: &Address) {
+        let mut list: Vec<Address> = env.storage().persistent().get(key).unwrap_or_else(|| Vec::new(env));
+        if let Some(index) = list.first_index_of(user) {
+            list.remove(index);
+            env.storage().persistent().set(key, &list);
+        }
+    }
+    
+    // Delegate up to `max_stake` of voting power to another profile, replacing
+    // any existing delegation. Chains are capped at MAX_DELEGATION_DEPTH hops.
+    pub fn delegate(env: Env, user: Address, to: Address, max_stake: i128) {
+        user.require_auth();
+        
+        if user == to {
+            panic!("Cannot delegate to yourself");
+        }
+        if max_stake <= 0 {
+            panic!("Max stake must be positive");
+        }
+        if !Self::has_profile(env.clone(), user.clone()) || !Self::has_profile(env.clone(), to.clone()) {
+            panic!("User does not have a profile");
+        }
+        
+        // The new chain runs from the deepest delegator of `user` through `to`
+        // to the end of the delegate's own chain; reject cycles and chains
+        // longer than the cap
+        let mut depth = Self::get_delegation_height(env.clone(), user.clone()) + 1;
+        let mut current = to.clone();
+        while let Some(next) = Self::get_delegation(env.clone(), current) {
+            if next.delegate == user {
+                panic!("Delegation would create a cycle");
+            }
+            depth += 1;
+            current = next.delegate;
+        }
+        if depth > MAX_DELEGATION_DEPTH {
+            panic!("Delegation chain is too deep");
+        }
+        
+        Self::remove_delegation(&env, &user);
+        
+        let mut delegators = Self::get_delegators(env.clone(), to.clone());
+        if delegators.len() >= MAX_DELEGATORS {
+            panic!("Delegate has too many delegators");
+        }
+        delegators.push_back(user.clone());
+        env.storage().persistent().set(&DataKey::Delegators(to.clone()), &delegators);
+        
+        let delegation = Delegation { delegate: to.clone(), max_stake };
+        env.storage().persistent().set(&DataKey::Delegation(user.clone()), &delegation);
+        Self::refresh_delegation_heights(&env, &to);
+        
+        env.events().publish((symbol_short!("delegate"), user), delegation);
+    }
+    
+    // Revoke a user's delegation
+    pub fn undelegate(env: Env, user: Address) {
+        user.require_auth();
+        
+        if !Self::remove_delegation(&env, &user) {
+            panic!("No delegation to revoke");
+        }
+        
+        env.events().publish((Symbol::new(&env, "undelegate"), user), ());
+    }
+    
+    // Get a user's outgoing delegation (if any)
+    pub fn get_delegation(env: Env, user: Address) -> Option<Delegation> {
+        env.storage().persistent().get(&DataKey::Delegation(user))
+    }
+    
+    // Get the users delegating directly to an address
+    pub fn get_delegators(env: Env, user: Address) -> Vec<Address> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Delegators(user))
+            .unwrap_or_else(|| Vec::new(&env))
+    }
+    
+    // Clear a user's delegation and its reverse entry; returns whether one existed
+    fn remove_delegation(env: &Env, user: &Address) -> bool {
+        let delegation = match Self::get_delegation(env.clone(), user.clone()) {
+            Some(delegation) => delegation,
+            None => return false,
+        };
+        
+        let mut delegators = Self::get_delegators(env.clone(), delegation.delegate.clone());
+        if let Some(index) = delegators.first_index_of(user) {
+            delegators.remove(index);
+        }
+        env.storage().persistent().set(&DataKey::Delegators(delegation.delegate.clone()), &delegators);
+        env.storage().persistent().remove(&DataKey::Delegation(user.clone()));
+        Self::refresh_delegation_heights(env, &delegation.delegate);
+        
+        true
+    }
+    
+    // Get the delegators whose chains reach `delegate`, nearest first, up to
+    // `limit` (at most MAX_DELEGATED_VOTES). Chains are cut at any address in
+    // `voters`, since a user who votes carries their own delegators.
+    pub fn get_delegated_votes(env: Env, delegate: Address, voters: Vec<Address>, limit: u32) -> Vec<DelegatedVote> {
+        Self::collect_delegated_votes(&env, delegate, &voters, limit.min(MAX_DELEGATED_VOTES))
+    }
+    
+    // Get the delegated votes reaching each voter in one call, in the order of
+    // `voters`. Each voter counts at most MAX_DELEGATED_VOTES delegators and the
+    // whole tally at most MAX_TALLY_DELEGATED_VOTES; once that runs out, later
+    // voters get no delegated votes.
+    pub fn get_tally_delegated_votes(env: Env, voters: Vec<Address>) -> Vec<Vec<DelegatedVote>> {
+        let mut remaining = MAX_TALLY_DELEGATED_VOTES;
+        let mut tally = Vec::new(&env);
+        for voter in voters.iter() {
+            let votes = Self::collect_delegated_votes(&env, voter, &voters, remaining.min(MAX_DELEGATED_VOTES));
+            remaining -= votes.len();
+            tally.push_back(votes);
+        }
+        tally
+    }
+    
+    // Walk the delegation chains ending at `delegate`, breadth first, until
+    // `limit` delegators have been collected
+    fn collect_delegated_votes(env: &Env, delegate: Address, voters: &Vec<Address>, limit: u32) -> Vec<DelegatedVote> {
+        let mut votes = Vec::new(env);
+        let mut frontier = Vec::new(env);
+        frontier.push_back(delegate);
+        
+        for _ in 0..MAX_DELEGATION_DEPTH {
+            let mut next = Vec::new(env);
+            for current in frontier.iter() {
+                for delegator in Self::get_delegators(env.clone(), current).iter() {
+                    if voters.contains(&delegator) {
+                        continue;
+                    }
+                    if votes.len() >= limit {
+                        return votes;
+                    }
+                    
+                    let delegation = Self::get_delegation(env.clone(), delegator.clone()).unwrap();
+                    votes.push_back(DelegatedVote {
+                        delegator: delegator.clone(),
+                        max_stake: delegation.max_stake,
+                        attestation_weight: Self::get_attestation_weight(env.clone(), delegator.clone()),
+                    });
+                    next.push_back(delegator);
+                }
+            }
+            frontier = next;
+        }
+        
+        votes
+    }
+    
+    // Get the number of hops in the longest delegation chain ending at a user
+    pub fn get_delegation_height(env: Env, user: Address) -> u32 {
+        env.storage().persistent().get(&DataKey::DelegationHeight(user)).unwrap_or(0)
+    }
+    
+    // Recompute delegation heights from `start` to the end of its chain
+    fn refresh_delegation_heights(env: &Env, start: &Address) {
+        let mut current = Some(start.clone());
+        while let Some(user) = current {
+            let mut height = 0;
+            for delegator in Self::get_delegators(env.clone(), user.clone()).iter() {
+                height = height.max(Self::get_delegation_height(env.clone(), delegator) + 1);
+            }
+            
+            let key = DataKey::DelegationHeight(user.clone());
+            if height == 0 {
+                env.storage().persistent().remove(&key);
+            } else {
+                env.storage().persistent().set(&key, &height);
+            }
+            
+            current = Self::get_delegation(env.clone(), user).map(|delegation| delegation.delegate);
+        }
+    }
+    
+    // Remove the next page of a deleted profile's history; returns the entries left
+    pub fn purge_history(env: Env, user: Address) -> u32 {
+        if !env.storage().persistent().has(&DataKey::PendingPurge(user.clone())) {
+            panic!("No history is pending removal");
+        }
+        
+        Self::purge_history_page(&env, &user)
+    }
+    
+    // Get how many history entries of a deleted profile are still stored
+    pub fn get_pending_purge(env: Env, user: Address) -> u32 {
+        env.storage().persistent().get(&DataKey::PendingPurge(user)).unwrap_or(0)
+    }
+    
+    // Remove up to a page of history entries, newest first
+    fn purge_history_page(env: &Env, user: &Address) -> u32 {
+        let key = DataKey::PendingPurge(user.clone());
+        let remaining: u32 = env.storage().persistent().get(&key).unwrap_or(0);
+        let purged = remaining.min(MAX_HISTORY_PAGE);
+        
+        for index in (remaining - purged)..remaining {
+            env.storage().persistent().remove(&DataKey::HistoryEntry(user.clone(), index));
+        }
+        
+        let remaining = remaining - purged;
+        if remaining == 0 {
+            env.storage().persistent().remove(&key);
+        } else {
+            env.storage().persistent().set(&key, &remaining);
+        }
+        remaining
+    }
+    
+    // SHA-256 of an address's XDR. Not an anonymization: hashing a known
+    // address recovers the link.
+    fn address_hash(env: &Env, user: &Address) -> BytesN<32> {
+        env.crypto().sha256(&user.clone().to_xdr(env))
+    }
+    
+    // Get the anonymized counters carried over from deleted profiles
+    pub fn get_deleted_totals(env: Env) -> DeletedTotals {
+        env.storage().instance().get(&DataKey::DeletedTotals).unwrap_or(DeletedTotals {
+            profiles: 0,
+            verification_count: 0,
+            accurate_count: 0,
+            rewards_earned: 0,
+        })
+    }
+    
+    // Look up the address that owns a username (case-insensitive)
+    pub fn resolve_username(env: Env, username: Symbol) -> Option<Address> {
+        let normalized = Self::normalize_username(&env, &username);
+        env.storage().persistent().get(&DataKey::Username(normalized))
+    }
+    
+    // Get the reserved username blocklist
+    pub fn get_reserved_usernames(env: Env) -> Vec<Symbol> {
+        env.storage().instance().get(&DataKey::ReservedUsernames).unwrap_or_else(|| Vec::new(&env))
+    }
+    
+    // Add or remove a reserved username (admin only)
+    pub fn set_username_reserved(env: Env, admin: Address, username: Symbol, reserved: bool) {
+        // Verify the admin is calling
+        let stored_admin = Self::read_admin(&env);
+        if admin != stored_admin {
+            panic!("Only admin can reserve usernames");
+        }
+        admin.require_auth();
+        
+        let normalized = Self::normalize_username(&env, &username);
+        let mut names = Self::get_reserved_usernames(env.clone());
+        match (names.first_index_of(&normalized), reserved) {
+            (None, true) => names.push_back(normalized),
+            (Some(index), false) => {
+                names.remove(index);
+            },
+            _ => return,
+        }
+        env.storage().instance().set(&DataKey::ReservedUsernames, &names);
+    }
+    
+    // Register a normalized username to a user, panicking if it is unavailable
+    fn claim_username(env: &Env, normalized: &Symbol, user: &Address) {
+        let reserved: Vec<Symbol> = env.storage().instance().get(&DataKey::ReservedUsernames).unwrap_or_else(|| Vec::new(env));
+        if reserved.contains(normalized) {
+            panic!("Username is reserved");
+        }
+        
+        let key = DataKey::Username(normalized.clone());
+        if env.storage().persistent().has(&key) {
+            panic!("Username is already taken");
+        }
+        env.storage().persistent().set(&key, user);
+    }
+    
+    // Lowercase a username so uniqueness and lookups are case-insensitive.
+    // Symbols cannot be read as strings on-chain, so the characters are taken
+    // from the XDR encoding: a 4-byte type tag, a 4-byte big-endian length,
+    // then the characters themselves.
+    fn normalize_username(env: &Env, username: &Symbol) -> Symbol {
+        let xdr = username.to_xdr(env);
+        let mut len_bytes = [0u8; 4];
+        xdr.slice(4..8).copy_into_slice(&mut len_bytes);
+        let len = u32::from_be_bytes(len_bytes) as usize;
+        
+        if !(MIN_USERNAME_LENGTH..=MAX_USERNAME_LENGTH).contains(&len) {
+            panic!("Username must be between 3 and 32 characters");
+        }
+        
+        let mut buf = [0u8; MAX_USERNAME_LENGTH];
+        xdr.slice(8..8 + len as u32).copy_into_slice(&mut buf[..len]);
+        buf[..len].make_ascii_lowercase();
+        Symbol::new(env, core::str::from_utf8(&buf[..len]).unwrap())
+    }
+    
     // Record a verification by a user
     pub fn record_verification(
         env: Env,
@@ -144,9 +967,10 @@ impl UserProfileContract {
         stake_amount: i128,
         consensus_match: bool,
         reward_amount: i128,
+        consensus_percentage: u32,  // Share of stake on the winning side (50-100)
     ) {
         // Only the admin can record verifications
-        let stored_admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        let stored_admin = Self::read_admin(&env);
         if admin != stored_admin {
             panic!("Only admin can record verifications");
         }
@@ -168,95 +992,179 @@ impl UserProfileContract {
             reward_amount,
         };
         
-        // Add to user's verification history
-        let mut history_map: Map<Address, Vec<VerificationRecord>> = env.storage().instance().get(&DataKey::VerificationHistory).unwrap();
-        let mut user_history = history_map.get(user.clone()).unwrap_or_else(|| Vec::new(&env));
-        user_history.push_back(record);
-        history_map.set(user.clone(), user_history.clone());
-        env.storage().instance().set(&DataKey::VerificationHistory, &history_map);
-        
         // Update user profile stats
         let mut user_profile = profiles.get(user.clone()).unwrap();
         
-        // Update verification count
+        // Append to user's verification history at the next index
+        let key = DataKey::HistoryEntry(user.clone(), user_profile.verification_count);
+        env.storage().persistent().set(&key, &record);
+        env.storage()
+            .persistent()
+            .extend_ttl(&key, HISTORY_LIFETIME_THRESHOLD, HISTORY_BUMP_AMOUNT);
+        
+        // Update running counters
         user_profile.verification_count += 1;
+        if consensus_match {
+            user_profile.accurate_count += 1;
+            user_profile.current_streak += 1;
+            user_profile.longest_streak = user_profile.longest_streak.max(user_profile.current_streak);
+        } else {
+            user_profile.current_streak = 0;
+        }
         
         // Update rewards earned
         user_profile.rewards_earned += reward_amount;
         
         // Calculate new accuracy percentage
-        let accurate_count = user_history.iter().filter(|r| r.consensus_match).count() as u32;
-        let total_count = user_history.len() as u32;
-        user_profile.accuracy_percentage = if total_count > 0 {
-            (accurate_count * 100) / total_count
-        } else {
-            0
-        };
+        user_profile.accuracy_percentage = (user_profile.accurate_count * 100) / user_profile.verification_count;
         
         // Update profile
         profiles.set(user.clone(), user_profile);
         env.storage().instance().set(&DataKey::Profiles, &profiles);
         
         // Update reputation
-        Self::update_reputation(env.clone(), admin, user, consensus_match);
+        Self::update_reputation(env.clone(), admin.clone(), user.clone(), consensus_match, stake_amount, consensus_percentage);
         
         // Check if level should be updated
-        Self::check_level_up(env.clone(), admin, user);
+        Self::check_level_up(env.clone(), user.clone());
+        
+        // Refresh the user's leaderboard positions
+        Self::update_leaderboards(&env, &user, reward_amount);
+        
+        // Mint badges for any milestones reached
+        Self::award_achievements(&env, &user);
     }
     
-    // Update user reputation based on verification accuracy
-    fn update_reputation(env: Env, admin: Address, user: Address, consensus_match: bool) {
-        let mut reputation_map: Map<Address, u32> = env.storage().instance().get(&DataKey::Reputation).unwrap();
-        let current_reputation = reputation_map.get(user.clone()).unwrap_or(100);
+    // Link the achievement badge contract; this contract must be its minter (admin only)
+    pub fn set_badge_contract(env: Env, admin: Address, badges: Address) {
+        // Verify the admin is calling
+        let stored_admin = Self::read_admin(&env);
+        if admin != stored_admin {
+            panic!("Only admin can set the badge contract");
+        }
+        admin.require_auth();
+        
+        env.storage().instance().set(&DataKey::BadgeContract, &badges);
+    }
+    
+    // Get the linked achievement badge contract (if any)
+    pub fn get_badge_contract(env: Env) -> Option<Address> {
+        env.storage().instance().get(&DataKey::BadgeContract)
+    }
+    
+    // Mint a badge for each milestone the user has reached but not yet been awarded
+    fn award_achievements(env: &Env, user: &Address) {
+        let badges = match Self::get_badge_contract(env.clone()) {
+            Some(badges) => AchievementBadgesClient::new(env, &badges),
+            None => return,
+        };
+        let profile = Self::get_profile(env.clone(), user.clone());
+        
+        let milestones = [
+            (Achievement::FirstVerification, profile.verification_count >= 1),
+            (Achievement::HundredAccurate, profile.accurate_count >= ACCURATE_MILESTONE),
+            (Achievement::Streak, profile.longest_streak >= STREAK_MILESTONE),
+        ];
+        for (achievement, reached) in milestones {
+            if reached && !badges.has_badge(user, &achievement) {
+                badges.mint(&env.current_contract_address(), user, &achievement);
+            }
+        }
+    }
+    
+    // Update user reputation based on verification accuracy. Penalties grow
+    // with the stake at risk and with how clear-cut the consensus was.
+    fn update_reputation(env: Env, admin: Address, user: Address, consensus_match: bool, stake_amount: i128, consensus_percentage: u32) {
+        let params = Self::get_reputation_params(env.clone());
+        
+        // Start from the decayed score so inactivity is applied before this change
+        let current_reputation = Self::get_reputation(env.clone(), user.clone());
         
         // Calculate new reputation
         let new_reputation = if consensus_match {
             // Reward for correct verification
-            if current_reputation < 980 {
-                current_reputation + 20
-            } else {
-                1000 // Max reputation
-            }
+            (current_reputation + params.reward).min(MAX_REPUTATION)
         } else {
-            // Penalty for incorrect verification
-            if current_reputation > 30 {
-                current_reputation - 30
+            // Up to double the penalty for large stakes
+            let stake_bonus = if params.stake_unit > 0 {
+                (stake_amount.max(0) * 100 / params.stake_unit).min(100) as u32
             } else {
-                1 // Min reputation
-            }
+                0
+            };
+            // Half the penalty for a 50/50 split, the full penalty for a unanimous one
+            let lopsidedness = consensus_percentage.clamp(50, 100);
+            let penalty = params.penalty * (100 + stake_bonus) / 100 * lopsidedness / 100;
+            
+            // Penalty for incorrect verification
+            current_reputation.saturating_sub(penalty).max(MIN_REPUTATION)
         };
         
         // Store updated reputation
-        reputation_map.set(user.clone(), new_reputation);
+        Self::write_reputation(&env, &user, new_reputation);
+    }
+    
+    // Store a reputation score as of now
+    fn write_reputation(env: &Env, user: &Address, reputation: u32) {
+        let mut reputation_map: Map<Address, u32> = env.storage().instance().get(&DataKey::Reputation).unwrap();
+        reputation_map.set(user.clone(), reputation);
         env.storage().instance().set(&DataKey::Reputation, &reputation_map);
+        
+        let mut updated_map: Map<Address, u64> = env.storage().instance().get(&DataKey::ReputationUpdated).unwrap_or_else(|| Map::new(env));
+        updated_map.set(user.clone(), env.ledger().timestamp());
+        env.storage().instance().set(&DataKey::ReputationUpdated, &updated_map);
     }
     
-    // Check if user should level up
-    fn check_level_up(env: Env, admin: Address, user: Address) {
-        let profiles: Map<Address, UserProfile> = env.storage().instance().get(&DataKey::Profiles).unwrap();
-        let user_profile = profiles.get(user.clone()).unwrap();
+    // Decay a stored score toward neutral: the distance halves every
+    // `half_life`, interpolating linearly within each half-life
+    fn decay_reputation(params: &ReputationParams, stored: u32, elapsed: u64) -> u32 {
+        if params.half_life == 0 {
+            return stored;
+        }
         
-        let mut level_map: Map<Address, u32> = env.storage().instance().get(&DataKey::Level).unwrap();
-        let current_level = level_map.get(user.clone()).unwrap_or(1);
+        let halvings = elapsed / params.half_life;
+        if halvings >= 32 {
+            return params.neutral;
+        }
         
-        // Level up criteria: verification count, accuracy, and reputation
-        let verification_count = user_profile.verification_count;
-        let accuracy = user_profile.accuracy_percentage;
+        let mut distance = (stored as i64 - params.neutral as i64) / (1i64 << halvings);
+        let remainder = (elapsed % params.half_life) as i64;
+        distance -= distance * remainder / (2 * params.half_life as i64);
         
-        let reputation_map: Map<Address, u32> = env.storage().instance().get(&DataKey::Reputation).unwrap();
-        let reputation = reputation_map.get(user.clone()).unwrap_or(100);
-        
-        // Determine new level based on criteria
-        let new_level = match (verification_count, accuracy, reputation) {
-            (vc, acc, rep) if vc >= 100 && acc >= 90 && rep >= 900 => 5,
-            (vc, acc, rep) if vc >= 50 && acc >= 80 && rep >= 800 => 4,
-            (vc, acc, rep) if vc >= 25 && acc >= 70 && rep >= 700 => 3,
-            (vc, acc, rep) if vc >= 10 && acc >= 60 && rep >= 600 => 2,
-            _ => 1,
-        };
+        (params.neutral as i64 + distance) as u32
+    }
+    
+    // Set the reputation model (admin only)
+    pub fn set_reputation_params(env: Env, admin: Address, params: ReputationParams) {
+        // Verify the admin is calling
+        let stored_admin = Self::read_admin(&env);
+        if admin != stored_admin {
+            panic!("Only admin can set reputation params");
+        }
+        admin.require_auth();
+        
+        if params.neutral < MIN_REPUTATION || params.neutral > MAX_REPUTATION {
+            panic!("Neutral reputation is out of range");
+        }
+        if params.stake_unit < 0 {
+            panic!("Stake unit must be non-negative");
+        }
+        
+        env.storage().instance().set(&DataKey::ReputationParams, &params);
+    }
+    
+    // Get the reputation model
+    pub fn get_reputation_params(env: Env) -> ReputationParams {
+        env.storage().instance().get(&DataKey::ReputationParams).unwrap()
+    }
+    
+    // Move the user to the tier matching their current stats, up or down
+    fn check_level_up(env: Env, user: Address) {
+        let mut level_map: Map<Address, u32> = env.storage().instance().get(&DataKey::Level).unwrap();
+        let current_level = level_map.get(user.clone()).unwrap_or(1);
+        let new_level = Self::derive_level(&env, &user);
         
         // Update level if it has changed
-        if new_level > current_level {
+        if new_level != current_level {
             // Update level map
             level_map.set(user.clone(), new_level);
             env.storage().instance().set(&DataKey::Level, &level_map);
@@ -267,7 +1175,78 @@ impl UserProfileContract {
             updated_profile.level = new_level;
             profiles.set(user.clone(), updated_profile);
             env.storage().instance().set(&DataKey::Profiles, &profiles);
+            
+            env.events().publish((Symbol::new(&env, "level_changed"), user), (current_level, new_level));
+        }
+    }
+    
+    // Highest tier whose requirements the user meets right now. Reputation decays
+    // and tiers can be retuned, so this can differ from the stored level.
+    fn derive_level(env: &Env, user: &Address) -> u32 {
+        let profiles: Map<Address, UserProfile> = env.storage().instance().get(&DataKey::Profiles).unwrap();
+        let user_profile = profiles.get(user.clone()).unwrap();
+        
+        // Level criteria: verification count, accuracy, and reputation
+        let verification_count = user_profile.verification_count;
+        let accuracy = user_profile.accuracy_percentage;
+        
+        let reputation = Self::get_reputation(env.clone(), user.clone());
+        
+        // Determine the highest tier whose requirements are met
+        let tiers = Self::get_level_tiers(env.clone());
+        let mut level = 1;
+        for tier in tiers.iter() {
+            if verification_count >= tier.min_verifications && accuracy >= tier.min_accuracy && reputation >= tier.min_reputation {
+                level = tier.level;
+            }
+        }
+        
+        level
+    }
+    
+    // Replace the level tiers (admin only). Tiers must be ordered by level,
+    // starting at a level 1 tier with no requirements.
+    pub fn set_level_tiers(env: Env, admin: Address, tiers: Vec<LevelTier>) {
+        // Verify the admin is calling
+        let stored_admin = Self::read_admin(&env);
+        if admin != stored_admin {
+            panic!("Only admin can set level tiers");
+        }
+        admin.require_auth();
+        
+        let base = tiers.first().unwrap_or_else(|| panic!("At least one level tier is required"));
+        if base.level != 1 || base.min_verifications != 0 || base.min_accuracy != 0 || base.min_reputation != 0 {
+            panic!("The first tier must be level 1 with no requirements");
+        }
+        
+        for i in 1..tiers.len() {
+            let previous = tiers.get(i - 1).unwrap();
+            let tier = tiers.get(i).unwrap();
+            if tier.level <= previous.level {
+                panic!("Level tiers must be in increasing level order");
+            }
+            if tier.min_accuracy > 100 {
+                panic!("Accuracy requirement must be at most 100");
+            }
         }
+        
+        env.storage().instance().set(&DataKey::LevelTiers, &tiers);
+    }
+    
+    // Get the level tiers
+    pub fn get_level_tiers(env: Env) -> Vec<LevelTier> {
+        env.storage().instance().get(&DataKey::LevelTiers).unwrap()
+    }
+    
+    // Get the perks of a user's current level
+    pub fn get_level_perks(env: Env, user: Address) -> Vec<Symbol> {
+        let level = Self::get_level(env.clone(), user);
+        for tier in Self::get_level_tiers(env.clone()).iter() {
+            if tier.level == level {
+                return tier.perks;
+            }
+        }
+        Vec::new(&env)
     }
     
     // Get a user's profile
@@ -278,80 +1257,397 @@ impl UserProfileContract {
             panic!("User does not have a profile");
         }
         
-        profiles.get(user).unwrap()
+        let mut profile = profiles.get(user.clone()).unwrap();
+        profile.level = Self::derive_level(&env, &user);
+        profile
     }
     
-    // Get a user's verification history
-    pub fn get_verification_history(env: Env, user: Address) -> Vec<VerificationRecord> {
-        let history_map: Map<Address, Vec<VerificationRecord>> = env.storage().instance().get(&DataKey::VerificationHistory).unwrap();
+    // Check whether a user has a profile
+    pub fn has_profile(env: Env, user: Address) -> bool {
+        let profiles: Map<Address, UserProfile> = env.storage().instance().get(&DataKey::Profiles).unwrap();
+        
+        profiles.contains_key(user)
+    }
+    
+    // Get a user's reputation, level and accuracy in one call (None without a profile)
+    pub fn get_reputation_summary(env: Env, user: Address) -> Option<ReputationSummary> {
+        let profiles: Map<Address, UserProfile> = env.storage().instance().get(&DataKey::Profiles).unwrap();
+        let profile = profiles.get(user.clone())?;
         
-        if !history_map.contains_key(&user) {
-            return Vec::new(&env);
+        Some(ReputationSummary {
+            user: user.clone(),
+            reputation: Self::get_reputation(env.clone(), user.clone()),
+            level: Self::get_level(env, user),
+            accuracy_percentage: profile.accuracy_percentage,
+            verification_count: profile.verification_count,
+            rewards_earned: profile.rewards_earned,
+        })
+    }
+    
+    // Register an attestor or change its weight (admin only)
+    pub fn set_attestor(env: Env, admin: Address, attestor: Address, kind: AttestationKind, weight: u32) {
+        // Verify the admin is calling
+        let stored_admin = Self::read_admin(&env);
+        if admin != stored_admin {
+            panic!("Only admin can register attestors");
+        }
+        admin.require_auth();
+        
+        env.storage().persistent().set(&DataKey::Attestor(attestor.clone()), &Attestor { kind, weight });
+        env.events().publish((symbol_short!("attestor"), symbol_short!("set")), (attestor, kind, weight));
+    }
+    
+    // Deregister an attestor, voiding every attestation it issued (admin only)
+    pub fn remove_attestor(env: Env, admin: Address, attestor: Address) {
+        // Verify the admin is calling
+        let stored_admin = Self::read_admin(&env);
+        if admin != stored_admin {
+            panic!("Only admin can remove attestors");
+        }
+        admin.require_auth();
+        
+        env.storage().persistent().remove(&DataKey::Attestor(attestor.clone()));
+        env.events().publish((symbol_short!("attestor"), symbol_short!("removed")), attestor);
+    }
+    
+    // Get a registered attestor (if any)
+    pub fn get_attestor(env: Env, attestor: Address) -> Option<Attestor> {
+        env.storage().persistent().get(&DataKey::Attestor(attestor))
+    }
+    
+    // Attest to a user's profile, replacing any earlier attestation by the same attestor
+    pub fn attest(env: Env, attestor: Address, user: Address, expires_at: Option<u64>) {
+        attestor.require_auth();
+        
+        let info = Self::get_attestor(env.clone(), attestor.clone()).unwrap_or_else(|| panic!("Not a registered attestor"));
+        if !Self::has_profile(env.clone(), user.clone()) {
+            panic!("User does not have a profile");
+        }
+        
+        let mut attestations = Self::get_attestations(env.clone(), user.clone());
+        if let Some(index) = attestations.iter().position(|a| a.attestor == attestor) {
+            attestations.remove(index as u32);
+        }
+        if attestations.len() >= MAX_ATTESTATIONS {
+            panic!("Too many attestations");
+        }
+        
+        attestations.push_back(Attestation {
+            attestor: attestor.clone(),
+            kind: info.kind,
+            issued_at: env.ledger().timestamp(),
+            expires_at,
+        });
+        env.storage().persistent().set(&DataKey::Attestations(user.clone()), &attestations);
+        
+        env.events().publish((symbol_short!("attest"), symbol_short!("issued")), (attestor, user, info.kind));
+    }
+    
+    // Revoke an attestation previously issued by the calling attestor
+    pub fn revoke_attestation(env: Env, attestor: Address, user: Address) {
+        attestor.require_auth();
+        
+        let mut attestations = Self::get_attestations(env.clone(), user.clone());
+        let index = attestations
+            .iter()
+            .position(|a| a.attestor == attestor)
+            .unwrap_or_else(|| panic!("No attestation from this attestor"));
+        attestations.remove(index as u32);
+        
+        if attestations.is_empty() {
+            env.storage().persistent().remove(&DataKey::Attestations(user.clone()));
+        } else {
+            env.storage().persistent().set(&DataKey::Attestations(user.clone()), &attestations);
+        }
+        
+        env.events().publish((symbol_short!("attest"), symbol_short!("revoked")), (attestor, user));
+    }
+    
+    // Get every attestation a user holds, including expired ones
+    pub fn get_attestations(env: Env, user: Address) -> Vec<Attestation> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Attestations(user))
+            .unwrap_or_else(|| Vec::new(&env))
+    }
+    
+    // Get the combined weight of a user's unexpired attestations from registered attestors
+    pub fn get_attestation_weight(env: Env, user: Address) -> u32 {
+        let now = env.ledger().timestamp();
+        let mut weight = 0u32;
+        
+        for attestation in Self::get_attestations(env.clone(), user).iter() {
+            if attestation.expires_at.is_some_and(|expires_at| expires_at <= now) {
+                continue;
+            }
+            if let Some(attestor) = Self::get_attestor(env.clone(), attestation.attestor) {
+                weight = weight.saturating_add(attestor.weight);
+            }
+        }
+        
+        weight
+    }
+    
+    // Get several users' attestation weights in one call, in order
+    pub fn get_attestation_weights(env: Env, users: Vec<Address>) -> Vec<u32> {
+        let mut weights = Vec::new(&env);
+        for user in users.iter() {
+            weights.push_back(Self::get_attestation_weight(env.clone(), user));
+        }
+        weights
+    }
+    
+    // Get a page of a user's verification history, oldest first
+    pub fn get_verification_history(env: Env, user: Address, start: u32, limit: u32) -> Vec<VerificationRecord> {
+        let profiles: Map<Address, UserProfile> = env.storage().instance().get(&DataKey::Profiles).unwrap();
+        let mut page = Vec::new(&env);
+        
+        let total = match profiles.get(user.clone()) {
+            Some(profile) => profile.verification_count,
+            None => return page,
+        };
+        
+        let end = start.saturating_add(limit.min(MAX_HISTORY_PAGE)).min(total);
+        for index in start..end {
+            if let Some(record) = Self::read_history_entry(&env, &user, index) {
+                page.push_back(record);
+            }
         }
         
-        history_map.get(user).unwrap()
+        page
+    }
+    
+    fn read_history_entry(env: &Env, user: &Address, index: u32) -> Option<VerificationRecord> {
+        let key = DataKey::HistoryEntry(user.clone(), index);
+        let record: Option<VerificationRecord> = env.storage().persistent().get(&key);
+        if record.is_some() {
+            env.storage()
+                .persistent()
+                .extend_ttl(&key, HISTORY_LIFETIME_THRESHOLD, HISTORY_BUMP_AMOUNT);
+        }
+        record
     }
     
-    // Get a user's reputation
+    // Get a user's reputation, decayed toward neutral for inactivity
     pub fn get_reputation(env: Env, user: Address) -> u32 {
         let reputation_map: Map<Address, u32> = env.storage().instance().get(&DataKey::Reputation).unwrap();
         
-        reputation_map.get(user).unwrap_or(0)
+        let stored = match reputation_map.get(user.clone()) {
+            Some(reputation) => reputation,
+            None => return 0,
+        };
+        
+        // Apply decay for the time since the score was last written
+        let updated_map: Map<Address, u64> = env.storage().instance().get(&DataKey::ReputationUpdated).unwrap_or_else(|| Map::new(&env));
+        let updated_at = updated_map.get(user).unwrap_or(env.ledger().timestamp());
+        let elapsed = env.ledger().timestamp().saturating_sub(updated_at);
+        
+        let params = Self::get_reputation_params(env.clone());
+        Self::decay_reputation(&params, stored, elapsed)
     }
     
     // Get a user's level
+    // Get the user's level, derived from their current stats so that decay and
+    // tier changes show up without waiting for their next verification
     pub fn get_level(env: Env, user: Address) -> u32 {
-        let level_map: Map<Address, u32> = env.storage().instance().get(&DataKey::Level).unwrap();
+        if Self::has_profile(env.clone(), user.clone()) {
+            return Self::derive_level(&env, &user);
+        }
         
+        let level_map: Map<Address, u32> = env.storage().instance().get(&DataKey::Level).unwrap();
         level_map.get(user).unwrap_or(1)
     }
     
-    // Get top users by reputation
+    // Write the user's derived level back to storage and the leaderboards.
+    // Anyone can call this; it only records what `get_level` already reports.
+    pub fn refresh_level(env: Env, user: Address) -> u32 {
+        if !Self::has_profile(env.clone(), user.clone()) {
+            panic!("User does not have a profile");
+        }
+        
+        Self::check_level_up(env.clone(), user.clone());
+        Self::update_leaderboards(&env, &user, 0);
+        
+        Self::get_level(env, user)
+    }
+    
+    // Get top users by reputation. Served from the all-time reputation board,
+    // so at most LEADERBOARD_SIZE (50) users are ever returned.
     pub fn get_top_users(env: Env, limit: u32) -> Vec<UserProfile> {
         let profiles: Map<Address, UserProfile> = env.storage().instance().get(&DataKey::Profiles).unwrap();
-        let reputation_map: Map<Address, u32> = env.storage().instance().get(&DataKey::Reputation).unwrap();
+        let entries = Self::get_leaderboard(env.clone(), LeaderboardKind::Reputation, LeaderboardPeriod::AllTime, 0, limit);
         
-        // Create a vector to hold users and their reputations
-        let mut user_rep_pairs: Vec<(Address, u32)> = Vec::new(&env);
+        let mut users: Vec<UserProfile> = Vec::new(&env);
+        for entry in entries.iter() {
+            if let Some(profile) = profiles.get(entry.user) {
+                users.push_back(profile);
+            }
+        }
         
-        // Collect all users and their reputations
-        for (address, _) in profiles.iter() {
-            let rep = reputation_map.get(address.clone()).unwrap_or(0);
-            user_rep_pairs.push_back((address, rep));
+        users
+    }
+    
+    // Get a page of a leaderboard for the current epoch, highest score first.
+    // Boards only keep the top LEADERBOARD_SIZE (50) entries.
+    pub fn get_leaderboard(env: Env, kind: LeaderboardKind, period: LeaderboardPeriod, start: u32, limit: u32) -> Vec<LeaderboardEntry> {
+        let epoch = Self::leaderboard_epoch(&env, period);
+        let mut board = Self::read_leaderboard(&env, kind, period, epoch);
+        if kind == LeaderboardKind::Reputation {
+            board = Self::decay_leaderboard(&env, &board);
         }
         
-        // Sort by reputation (descending)
-        // Note: In a real implementation, this would need a proper sorting algorithm
-        // This is simplified for demonstration purposes
-        let mut sorted_users: Vec<UserProfile> = Vec::new(&env);
-        let mut remaining_pairs = user_rep_pairs.clone();
+        let start = start.min(board.len());
+        let end = start.saturating_add(limit).min(board.len());
+        board.slice(start..end)
+    }
+    
+    // Write the user's latest scores into every leaderboard
+    fn update_leaderboards(env: &Env, user: &Address, reward_amount: i128) {
+        let profile = Self::get_profile(env.clone(), user.clone());
+        let reputation = Self::get_reputation(env.clone(), user.clone()) as i128;
         
-        for _ in 0..std::cmp::min(limit as u32, user_rep_pairs.len() as u32) {
-            if remaining_pairs.len() == 0 {
-                break;
-            }
+        for period in [LeaderboardPeriod::AllTime, LeaderboardPeriod::Weekly, LeaderboardPeriod::Monthly] {
+            let epoch = Self::leaderboard_epoch(env, period);
             
-            // Find the highest reputation user
-            let mut highest_rep = 0;
-            let mut highest_index = 0;
+            let rewards = if period == LeaderboardPeriod::AllTime {
+                profile.rewards_earned
+            } else {
+                let earned = Self::read_epoch_rewards(env, user, period, epoch) + reward_amount;
+                let key = DataKey::EpochRewards(user.clone(), period, epoch);
+                env.storage().temporary().set(&key, &earned);
+                let ttl = Self::epoch_data_ttl(period);
+                env.storage().temporary().extend_ttl(&key, ttl - DAY_IN_LEDGERS, ttl);
+                earned
+            };
             
-            for i in 0..remaining_pairs.len() {
-                let (_, rep) = remaining_pairs.get_unchecked(i).unwrap();
-                if rep > highest_rep {
-                    highest_rep = rep;
-                    highest_index = i;
+            Self::upsert_leaderboard(env, LeaderboardKind::Reputation, period, epoch, user, reputation);
+            Self::upsert_leaderboard(env, LeaderboardKind::Accuracy, period, epoch, user, profile.accuracy_percentage as i128);
+            Self::upsert_leaderboard(env, LeaderboardKind::RewardsEarned, period, epoch, user, rewards);
+            Self::upsert_leaderboard(env, LeaderboardKind::Level, period, epoch, user, profile.level as i128);
+        }
+    }
+    
+    // Move a user to their new position in a bounded, descending top-K list
+    fn upsert_leaderboard(env: &Env, kind: LeaderboardKind, period: LeaderboardPeriod, epoch: u64, user: &Address, score: i128) {
+        let mut board = Self::read_leaderboard(env, kind, period, epoch);
+        
+        // Drop the user's previous entry
+        for i in 0..board.len() {
+            if board.get(i).unwrap().user == *user {
+                board.remove(i);
+                break;
+            }
+        }
+        
+        // Insert after every entry with a score at least as high, so ties keep earlier holders first
+        let mut position = board.len();
+        for i in 0..board.len() {
+            if board.get(i).unwrap().score < score {
+                position = i;
+                break;
+            }
+        }
+        
+        if position < LEADERBOARD_SIZE {
+            board.insert(position, LeaderboardEntry { user: user.clone(), score });
+            if board.len() > LEADERBOARD_SIZE {
+                board.pop_back();
+            }
+        }
+        
+        Self::write_leaderboard(env, kind, period, epoch, &board);
+    }
+    
+    // Re-rank reputation entries by their decayed scores. Stored scores are
+    // as of each user's last verification; membership is still decided then.
+    fn decay_leaderboard(env: &Env, board: &Vec<LeaderboardEntry>) -> Vec<LeaderboardEntry> {
+        let mut ranked: Vec<LeaderboardEntry> = Vec::new(env);
+        
+        for entry in board.iter() {
+            let score = Self::get_reputation(env.clone(), entry.user.clone()) as i128;
+            let mut position = ranked.len();
+            for i in 0..ranked.len() {
+                if ranked.get(i).unwrap().score < score {
+                    position = i;
+                    break;
                 }
             }
-            
-            // Add this user to the sorted list
-            let (addr, _) = remaining_pairs.get_unchecked(highest_index).unwrap();
-            let profile = profiles.get(addr).unwrap();
-            sorted_users.push_back(profile);
-            
-            // Remove this user from the remaining pairs
-            remaining_pairs.remove(highest_index);
+            ranked.insert(position, LeaderboardEntry { user: entry.user, score });
         }
         
-        sorted_users
+        ranked
+    }
+    
+    // Drop a user's entry from a leaderboard, if present
+    fn remove_from_leaderboard(env: &Env, kind: LeaderboardKind, period: LeaderboardPeriod, epoch: u64, user: &Address) {
+        let mut board = Self::read_leaderboard(env, kind, period, epoch);
+        
+        for i in 0..board.len() {
+            if board.get(i).unwrap().user == *user {
+                board.remove(i);
+                Self::write_leaderboard(env, kind, period, epoch, &board);
+                return;
+            }
+        }
+    }
+    
+    fn read_leaderboard(env: &Env, kind: LeaderboardKind, period: LeaderboardPeriod, epoch: u64) -> Vec<LeaderboardEntry> {
+        let key = DataKey::Leaderboard(kind, period, epoch);
+        let board = if period == LeaderboardPeriod::AllTime {
+            env.storage().persistent().get(&key)
+        } else {
+            env.storage().temporary().get(&key)
+        };
+        board.unwrap_or_else(|| Vec::new(env))
+    }
+    
+    fn write_leaderboard(env: &Env, kind: LeaderboardKind, period: LeaderboardPeriod, epoch: u64, board: &Vec<LeaderboardEntry>) {
+        let key = DataKey::Leaderboard(kind, period, epoch);
+        if period == LeaderboardPeriod::AllTime {
+            env.storage().persistent().set(&key, board);
+        } else {
+            env.storage().temporary().set(&key, board);
+            let ttl = Self::epoch_data_ttl(period);
+            env.storage().temporary().extend_ttl(&key, ttl - DAY_IN_LEDGERS, ttl);
+        }
+    }
+    
+    fn read_epoch_rewards(env: &Env, user: &Address, period: LeaderboardPeriod, epoch: u64) -> i128 {
+        env.storage()
+            .temporary()
+            .get(&DataKey::EpochRewards(user.clone(), period, epoch))
+            .unwrap_or(0)
+    }
+    
+    // Ledgers weekly and monthly data outlives its last write
+    fn epoch_data_ttl(period: LeaderboardPeriod) -> u32 {
+        match period {
+            LeaderboardPeriod::AllTime => 0,
+            LeaderboardPeriod::Weekly => WEEK_LEDGERS + DAY_IN_LEDGERS,
+            LeaderboardPeriod::Monthly => MONTH_LEDGERS + DAY_IN_LEDGERS,
+        }
+    }
+    
+    // Epochs whose leaderboard data may not have expired yet
+    fn live_epochs(env: &Env, period: LeaderboardPeriod) -> Vec<u64> {
+        let current = Self::leaderboard_epoch(env, period);
+        let mut epochs = Vec::new(env);
+        epochs.push_back(current);
+        if period != LeaderboardPeriod::AllTime {
+            for back in 1..=LIVE_PAST_EPOCHS.min(current) {
+                epochs.push_back(current - back);
+            }
+        }
+        epochs
+    }
+    
+    // Epochs are fixed-length windows since the Unix epoch; all-time uses a single epoch
+    fn leaderboard_epoch(env: &Env, period: LeaderboardPeriod) -> u64 {
+        let now = env.ledger().timestamp();
+        match period {
+            LeaderboardPeriod::AllTime => 0,
+            LeaderboardPeriod::Weekly => now / WEEK_SECONDS,
+            LeaderboardPeriod::Monthly => now / MONTH_SECONDS,
+        }
     }
 } 
\ No newline at end of file
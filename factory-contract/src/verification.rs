@@ -2,7 +2,15 @@
 // This contract manages the verification process for individual news items
 
 #![no_std]
-use soroban_sdk::{contract, contractimpl, contracttype, Address, BytesN, Env, Map, Symbol, Vec};
+use soroban_sdk::{contract, contractimpl, contracttype, symbol_short, Address, BytesN, Env, Map, Symbol, Vec};
+
+use crate::staking_vault::StakingVaultClient;
+use crate::token::TrueTokenClient;
+use crate::user_profile::{ReputationSummary, UserProfileContractClient};
+use crate::{fee_amount, FeeConfig, FeeRoute, TrueLensContractClient};
+
+// Upper bound on verifications per news item, keeping the tally affordable
+const MAX_VERIFICATIONS: u32 = 50;
 
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -14,6 +22,13 @@ pub enum DataKey {
     RewardPool,
     ConsensusReached,
     IsClosed,
+    Factory,       // Factory contract holding the protocol fee schedule
+    TreasuryFees,  // Fees owed to the protocol treasury
+    PayoutPool,    // Amount shared among majority verifiers after settlement
+    SybilPolicy,   // Attestation requirements for verifiers
+    SnapshotLedger,  // Ledger whose token balances back delegated votes
+    StakerYield,     // Slashed stake owed to the staking vault
+    FinalConsensus,  // Weighted consensus fixed when the item closed
 }
 
 #[contracttype]
@@ -42,6 +57,16 @@ pub struct Verification {
     pub timestamp: u64,
 }
 
+// Attestation requirements for voting. Verifiers need at least `min_weight`
+// attestation weight to vote, and their stake counts toward consensus in
+// proportion to their weight, in full from `full_weight` upward.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SybilPolicy {
+    pub min_weight: u32,
+    pub full_weight: u32,
+}
+
 #[contract]
 pub struct NewsVerification;
 
@@ -82,6 +107,46 @@ impl NewsVerification {
         env.storage().instance().set(&DataKey::RewardPool, &0i128);
         env.storage().instance().set(&DataKey::ConsensusReached, &false);
         env.storage().instance().set(&DataKey::IsClosed, &false);
+        
+        // Delegated votes are backed by balances from before the item opened
+        env.storage().instance().set(&DataKey::SnapshotLedger, &env.ledger().sequence().saturating_sub(1));
+    }
+    
+    // Link the factory whose fee schedule applies to this verification (admin only)
+    pub fn set_factory(env: Env, admin: Address, factory: Address) {
+        // Verify the admin is calling
+        let stored_admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        if admin != stored_admin {
+            panic!("Only admin can set the factory");
+        }
+        admin.require_auth();
+        
+        env.storage().instance().set(&DataKey::Factory, &factory);
+    }
+    
+    // Set the attestation requirements for verifiers (admin only)
+    pub fn set_sybil_policy(env: Env, admin: Address, policy: Option<SybilPolicy>) {
+        // Verify the admin is calling
+        let stored_admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        if admin != stored_admin {
+            panic!("Only admin can set the sybil policy");
+        }
+        admin.require_auth();
+        
+        match policy {
+            Some(policy) => {
+                if policy.full_weight == 0 || policy.full_weight < policy.min_weight {
+                    panic!("Full weight must be positive and at least the minimum weight");
+                }
+                env.storage().instance().set(&DataKey::SybilPolicy, &policy);
+            },
+            None => env.storage().instance().remove(&DataKey::SybilPolicy),
+        }
+    }
+    
+    // Get the attestation requirements for verifiers (None means anyone can vote at full weight)
+    pub fn get_sybil_policy(env: Env) -> Option<SybilPolicy> {
+        env.storage().instance().get(&DataKey::SybilPolicy)
     }
     
     // Submit a verification (verified or flagged) with stake
@@ -105,17 +170,36 @@ impl NewsVerification {
             panic!("Stake amount must be positive");
         }
         
+        // Take the protocol fee; only the remaining stake counts toward consensus
+        let fee = match Self::fee_config(&env) {
+            Some(config) => {
+                let fee = fee_amount(stake_amount, config.stake_fee_bps);
+                Self::route_fee(&env, &config, fee);
+                fee
+            },
+            None => 0,
+        };
+        let net_stake = stake_amount - fee;
+        
+        // Check the verifier's attestations against the sybil policy. The weight
+        // their stake carries is read again when the votes are tallied.
+        Self::require_attested(&env, &verifier);
+        
         // Create the verification record
         let verification = Verification {
             verifier: verifier.clone(),
             status,
-            stake: stake_amount,
+            stake: net_stake,
             timestamp: env.ledger().timestamp(),
         };
         
         // Add to verifications list
         let mut verifications: Vec<Verification> = env.storage().instance().get(&DataKey::Verifications).unwrap();
         
+        if verifications.len() >= MAX_VERIFICATIONS {
+            panic!("Verification limit reached");
+        }
+        
         // Check if verifier has already submitted a verification
         for i in 0..verifications.len() {
             let existing = verifications.get_unchecked(i).unwrap();
@@ -130,8 +214,12 @@ impl NewsVerification {
         
         // Update total staked amount
         let mut staked_amount: i128 = env.storage().instance().get(&DataKey::StakedAmount).unwrap();
-        staked_amount += stake_amount;
+        staked_amount += net_stake;
         env.storage().instance().set(&DataKey::StakedAmount, &staked_amount);
+        
+        if fee > 0 {
+            env.events().publish((symbol_short!("fee"), symbol_short!("stake")), (verifier, fee));
+        }
     }
     
     // Get the news item this contract is verifying
@@ -168,24 +256,53 @@ impl NewsVerification {
         (verified_count * 100) / total_count
     }
     
-    // Calculate consensus by staked amount (weighted voting)
+    // Calculate consensus by attestation-weighted stake (weighted voting).
+    // Once the item is closed this is the consensus fixed at close.
     pub fn calculate_weighted_consensus(env: Env) -> u32 {
+        if let Some(consensus) = env.storage().instance().get(&DataKey::FinalConsensus) {
+            return consensus;
+        }
+        
         let verifications: Vec<Verification> = env.storage().instance().get(&DataKey::Verifications).unwrap();
         
         if verifications.len() == 0 {
             return 0;
         }
         
+        let mut voters = Vec::new(&env);
+        for v in verifications.iter() {
+            voters.push_back(v.verifier);
+        }
+        
+        // Resolve the linked contracts once for the whole tally
+        let factory: Option<Address> = env.storage().instance().get(&DataKey::Factory);
+        let factory = factory.map(|factory| TrueLensContractClient::new(&env, &factory));
+        let profile = factory
+            .as_ref()
+            .and_then(|factory| factory.get_profile_contract())
+            .map(|profile| UserProfileContractClient::new(&env, &profile));
+        let token = factory
+            .as_ref()
+            .and_then(|factory| factory.get_token_contract())
+            .map(|token| TrueTokenClient::new(&env, &token));
+        
+        let weights = Self::tally_weights(&env, profile.as_ref(), &voters);
+        let delegated = match (&profile, &token) {
+            (Some(profile), Some(token)) => Self::delegated_powers(&env, profile, token, &voters),
+            _ => Vec::new(&env),
+        };
+        
         let mut verified_stake = 0i128;
         let mut total_stake = 0i128;
-        
         for i in 0..verifications.len() {
             let v = verifications.get_unchecked(i).unwrap();
+            let weight = weights.get(i).unwrap_or(0);
+            let counted = (v.stake * weight as i128 / 100).saturating_add(delegated.get(i).unwrap_or(0));
             match v.status {
-                VerificationStatus::Verified => verified_stake += v.stake,
+                VerificationStatus::Verified => verified_stake = verified_stake.saturating_add(counted),
                 VerificationStatus::Flagged => {}, // Don't add to verified stake
             }
-            total_stake += v.stake;
+            total_stake = total_stake.saturating_add(counted);
         }
         
         if total_stake == 0 {
@@ -193,7 +310,7 @@ impl NewsVerification {
         }
         
         // Calculate percentage (0-100)
-        ((verified_stake * 100) / total_stake) as u32
+        (verified_stake.saturating_mul(100) / total_stake) as u32
     }
     
     // Close the verification process and distribute rewards
@@ -211,8 +328,9 @@ impl NewsVerification {
             panic!("Verification already closed");
         }
         
-        // Calculate final consensus
+        // Calculate final consensus and fix it so later reads skip the tally
         let consensus = Self::calculate_weighted_consensus(env.clone());
+        env.storage().instance().set(&DataKey::FinalConsensus, &consensus);
         
         // Set consensus reached if more than 50%
         let consensus_reached = consensus > 50;
@@ -221,8 +339,259 @@ impl NewsVerification {
         // Mark as closed
         env.storage().instance().set(&DataKey::IsClosed, &true);
         
-        // Here we would distribute rewards, but that would be handled by
-        // a token contract in a real implementation
+        // The minority's stake plus the reward pool is shared among the majority,
+        // less the settlement fee
+        let winning_status = if consensus_reached { VerificationStatus::Verified } else { VerificationStatus::Flagged };
+        let verifications: Vec<Verification> = env.storage().instance().get(&DataKey::Verifications).unwrap();
+        let mut losing_stake = 0i128;
+        for v in verifications.iter() {
+            if v.status != winning_status {
+                losing_stake += v.stake;
+            }
+        }
+        let reward_pool: i128 = env.storage().instance().get(&DataKey::RewardPool).unwrap();
+        
+        // Stakers in the vault take their configured share of the slashed stake
+        let config = Self::fee_config(&env);
+        let staker_yield = match &config {
+            Some(config) => fee_amount(losing_stake, config.slash_share_bps),
+            None => 0,
+        };
+        if staker_yield > 0 {
+            env.storage().instance().set(&DataKey::StakerYield, &staker_yield);
+            env.events().publish((symbol_short!("slash"), symbol_short!("yield")), staker_yield);
+        }
+        let distributable = losing_stake - staker_yield + reward_pool;
+        
+        let settlement_fee = match &config {
+            Some(config) => fee_amount(distributable, config.settlement_fee_bps),
+            None => 0,
+        };
+        if settlement_fee > 0 {
+            let treasury_fees: i128 = env.storage().instance().get(&DataKey::TreasuryFees).unwrap_or(0);
+            env.storage().instance().set(&DataKey::TreasuryFees, &(treasury_fees + settlement_fee));
+            env.events().publish((symbol_short!("fee"), symbol_short!("settle")), settlement_fee);
+        }
+        
+        env.storage().instance().set(&DataKey::PayoutPool, &(distributable - settlement_fee));
+        
+        // Token transfers for the payouts are handled by the token contract
+    }
+    
+    // Get what a verifier receives after settlement: their stake back plus a
+    // stake-weighted share of the payout pool, or nothing if they voted against
+    // the consensus
+    pub fn get_payout(env: Env, user: Address) -> i128 {
+        let is_closed: bool = env.storage().instance().get(&DataKey::IsClosed).unwrap();
+        if !is_closed {
+            panic!("Verification is not closed");
+        }
+        
+        let consensus_reached: bool = env.storage().instance().get(&DataKey::ConsensusReached).unwrap();
+        let winning_status = if consensus_reached { VerificationStatus::Verified } else { VerificationStatus::Flagged };
+        let verifications: Vec<Verification> = env.storage().instance().get(&DataKey::Verifications).unwrap();
+        
+        let mut winning_stake = 0i128;
+        let mut user_stake = 0i128;
+        for v in verifications.iter() {
+            if v.status == winning_status {
+                winning_stake += v.stake;
+                if v.verifier == user {
+                    user_stake = v.stake;
+                }
+            }
+        }
+        
+        if user_stake == 0 {
+            return 0;
+        }
+        
+        let payout_pool: i128 = env.storage().instance().get(&DataKey::PayoutPool).unwrap_or(0);
+        user_stake + payout_pool * user_stake / winning_stake
+    }
+    
+    // Get the protocol fees owed to the treasury
+    pub fn get_treasury_fees(env: Env) -> i128 {
+        env.storage().instance().get(&DataKey::TreasuryFees).unwrap_or(0)
+    }
+    
+    // Pay the fees owed to the treasury in the factory's fee config. This
+    // contract holds no tokens, so the admin transfers them from their own
+    // balance, like the rest of the settlement.
+    pub fn settle_treasury_fees(env: Env, admin: Address) -> i128 {
+        // Verify the admin is calling
+        let stored_admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        if admin != stored_admin {
+            panic!("Only admin can settle treasury fees");
+        }
+        admin.require_auth();
+        
+        let fees: i128 = env.storage().instance().get(&DataKey::TreasuryFees).unwrap_or(0);
+        if fees == 0 {
+            return 0;
+        }
+        
+        let config = Self::fee_config(&env).unwrap_or_else(|| panic!("No fee config set"));
+        let token = Self::token_contract(&env).unwrap_or_else(|| panic!("No token contract linked"));
+        TrueTokenClient::new(&env, &token).transfer(&admin, &config.treasury, &fees);
+        
+        env.storage().instance().set(&DataKey::TreasuryFees, &0i128);
+        env.events().publish((symbol_short!("fee"), symbol_short!("paid")), (config.treasury, fees));
+        
+        fees
+    }
+    
+    // Get the slashed stake owed to the staking vault
+    pub fn get_staker_yield(env: Env) -> i128 {
+        env.storage().instance().get(&DataKey::StakerYield).unwrap_or(0)
+    }
+    
+    // Pay the stakers' share of the slashed stake into the staking vault in the
+    // factory's fee config. The admin funds it, as with the treasury fees.
+    pub fn settle_staker_yield(env: Env, admin: Address) -> i128 {
+        // Verify the admin is calling
+        let stored_admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        if admin != stored_admin {
+            panic!("Only admin can settle staker yield");
+        }
+        admin.require_auth();
+        
+        let amount: i128 = env.storage().instance().get(&DataKey::StakerYield).unwrap_or(0);
+        if amount == 0 {
+            return 0;
+        }
+        
+        let config = Self::fee_config(&env).unwrap_or_else(|| panic!("No fee config set"));
+        StakingVaultClient::new(&env, &config.staking_vault).add_yield(&admin, &amount);
+        
+        env.storage().instance().set(&DataKey::StakerYield, &0i128);
+        
+        amount
+    }
+    
+    // Read the fee schedule from the linked factory (if any)
+    fn fee_config(env: &Env) -> Option<FeeConfig> {
+        let factory: Option<Address> = env.storage().instance().get(&DataKey::Factory);
+        factory.and_then(|factory| TrueLensContractClient::new(env, &factory).get_fee_config())
+    }
+    
+    // Get a verifier's reputation via the factory's linked profile contract
+    pub fn get_verifier_reputation(env: Env, user: Address) -> Option<ReputationSummary> {
+        let profile_contract = Self::profile_contract(&env)?;
+        UserProfileContractClient::new(&env, &profile_contract).get_reputation_summary(&user)
+    }
+    
+    // Panic if the verifier lacks the attestation weight the sybil policy requires
+    fn require_attested(env: &Env, verifier: &Address) {
+        if Self::get_sybil_policy(env.clone()).is_none() {
+            return;
+        }
+        
+        let profile_contract = Self::profile_contract(env).unwrap_or_else(|| panic!("No profile contract linked"));
+        let attestation_weight = UserProfileContractClient::new(env, &profile_contract).get_attestation_weight(verifier);
+        if Self::attested_weight(env, attestation_weight).is_none() {
+            panic!("Verifier is not sufficiently attested");
+        }
+    }
+    
+    // Get the percentage of each voter's stake that counts at tally time.
+    // Attestations can expire or be revoked after voting, so votes that no
+    // longer meet the sybil policy count for nothing.
+    fn tally_weights(env: &Env, profile: Option<&UserProfileContractClient>, voters: &Vec<Address>) -> Vec<u32> {
+        let mut weights = Vec::new(env);
+        if Self::get_sybil_policy(env.clone()).is_none() {
+            for _ in voters.iter() {
+                weights.push_back(100);
+            }
+            return weights;
+        }
+        
+        // Without a profile contract nobody is attested
+        let profile = match profile {
+            Some(profile) => profile,
+            None => return weights,
+        };
+        for attestation_weight in profile.get_attestation_weights(voters).iter() {
+            weights.push_back(Self::attested_weight(env, attestation_weight).unwrap_or(0));
+        }
+        weights
+    }
+    
+    // Apply the sybil policy to an attestation weight: the percentage of stake
+    // that counts, or None below the minimum
+    fn attested_weight(env: &Env, attestation_weight: u32) -> Option<u32> {
+        let policy = match Self::get_sybil_policy(env.clone()) {
+            Some(policy) => policy,
+            None => return Some(100),
+        };
+        
+        if attestation_weight < policy.min_weight {
+            return None;
+        }
+        Some((attestation_weight.min(policy.full_weight) as u64 * 100 / policy.full_weight as u64) as u32)
+    }
+    
+    // Sum the voting power delegated to each voter through the profile
+    // contract's delegation chains, in the order of `voters`. Each delegator
+    // counts at most their `max_stake`, capped by their token balance at the
+    // snapshot ledger and scaled by the sybil policy like a direct vote.
+    fn delegated_powers(env: &Env, profile: &UserProfileContractClient, token: &TrueTokenClient, voters: &Vec<Address>) -> Vec<i128> {
+        let snapshot: u32 = env.storage().instance().get(&DataKey::SnapshotLedger).unwrap_or(0);
+        let tally = profile.get_tally_delegated_votes(voters);
+        
+        // Read every delegator's snapshot balance in a single call
+        let mut delegators = Vec::new(env);
+        for votes in tally.iter() {
+            for vote in votes.iter() {
+                delegators.push_back(vote.delegator);
+            }
+        }
+        let balances = token.balances_at(&delegators, &snapshot);
+        
+        let mut powers = Vec::new(env);
+        let mut index = 0;
+        for votes in tally.iter() {
+            let mut power = 0i128;
+            for vote in votes.iter() {
+                let balance = balances.get(index).unwrap_or(0);
+                index += 1;
+                let weight = match Self::attested_weight(env, vote.attestation_weight) {
+                    Some(weight) => weight,
+                    None => continue,
+                };
+                let backed = vote.max_stake.min(balance).max(0);
+                power = power.saturating_add(backed * weight as i128 / 100);
+            }
+            powers.push_back(power);
+        }
+        
+        powers
+    }
+    
+    // Resolve the profile contract linked in the factory (if any)
+    fn profile_contract(env: &Env) -> Option<Address> {
+        let factory: Option<Address> = env.storage().instance().get(&DataKey::Factory);
+        factory.and_then(|factory| TrueLensContractClient::new(env, &factory).get_profile_contract())
+    }
+    
+    // Resolve the token contract linked in the factory (if any)
+    fn token_contract(env: &Env) -> Option<Address> {
+        let factory: Option<Address> = env.storage().instance().get(&DataKey::Factory);
+        factory.and_then(|factory| TrueLensContractClient::new(env, &factory).get_token_contract())
+    }
+    
+    // Send a stake fee to the treasury or this item's reward pool
+    fn route_fee(env: &Env, config: &FeeConfig, fee: i128) {
+        if fee == 0 {
+            return;
+        }
+        
+        let key = match config.route {
+            FeeRoute::Treasury => DataKey::TreasuryFees,
+            FeeRoute::RewardPool => DataKey::RewardPool,
+        };
+        let balance: i128 = env.storage().instance().get(&key).unwrap_or(0);
+        env.storage().instance().set(&key, &(balance + fee));
     }
     
     // Get verification status
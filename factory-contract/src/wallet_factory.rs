@@ -0,0 +1,96 @@
+// Wallet factory contract for TrueLens
+// This contract deploys one SmartWallet per passkey at a deterministic address
+
+use soroban_sdk::{contract, contractimpl, contracttype, symbol_short, Address, Bytes, BytesN, Env};
+
+use crate::smart_wallet::SmartWalletClient;
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum DataKey {
+    Admin,
+    WalletWasmHash,
+    Wallet(BytesN<32>),  // Deployed wallet keyed by credential salt
+}
+
+#[contract]
+pub struct WalletFactory;
+
+#[contractimpl]
+impl WalletFactory {
+    // Initialize the factory with the installed SmartWallet wasm hash
+    pub fn initialize(env: Env, admin: Address, wallet_wasm_hash: BytesN<32>) {
+        // Ensure the contract is not already initialized
+        if env.storage().instance().has(&DataKey::Admin) {
+            panic!("Contract already initialized");
+        }
+        
+        env.storage().instance().set(&DataKey::Admin, &admin);
+        env.storage().instance().set(&DataKey::WalletWasmHash, &wallet_wasm_hash);
+    }
+    
+    // Point future deployments at a new SmartWallet wasm (admin only)
+    pub fn update_wasm_hash(env: Env, admin: Address, wallet_wasm_hash: BytesN<32>) {
+        // Verify the admin is calling
+        let stored_admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        if admin != stored_admin {
+            panic!("Only admin can update the wallet wasm hash");
+        }
+        admin.require_auth();
+        
+        env.storage().instance().set(&DataKey::WalletWasmHash, &wallet_wasm_hash);
+    }
+    
+    // Deploy and initialize the wallet for a passkey credential in one call.
+    // The admin is the relayer that verified the passkey registration, so a
+    // third party cannot claim the credential's address with their own owner.
+    pub fn deploy_wallet(env: Env, credential_id: Bytes, owner: Address) -> Address {
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        admin.require_auth();
+        
+        let salt = Self::credential_salt(&env, &credential_id);
+        let key = DataKey::Wallet(salt.clone());
+        if env.storage().persistent().has(&key) {
+            panic!("Wallet already deployed for this credential");
+        }
+        
+        // Deploy the wallet and initialize it before anyone else can
+        let wasm_hash: BytesN<32> = env.storage().instance().get(&DataKey::WalletWasmHash).unwrap();
+        let wallet = env.deployer().with_current_contract(salt.clone()).deploy(wasm_hash);
+        SmartWalletClient::new(&env, &wallet).initialize(&owner);
+        
+        env.storage().persistent().set(&key, &wallet);
+        
+        env.events().publish(
+            (symbol_short!("wallet"), symbol_short!("deployed")),
+            (salt, wallet.clone(), owner),
+        );
+        
+        wallet
+    }
+    
+    // Compute the wallet address for a credential, whether or not it is deployed yet
+    pub fn get_wallet_address(env: Env, credential_id: Bytes) -> Address {
+        let salt = Self::credential_salt(&env, &credential_id);
+        env.deployer().with_current_contract(salt).deployed_address()
+    }
+    
+    // Check whether the wallet for a credential has been deployed
+    pub fn is_deployed(env: Env, credential_id: Bytes) -> bool {
+        let salt = Self::credential_salt(&env, &credential_id);
+        env.storage().persistent().has(&DataKey::Wallet(salt))
+    }
+    
+    // Get the SmartWallet wasm hash used for new deployments
+    pub fn get_wasm_hash(env: Env) -> BytesN<32> {
+        env.storage().instance().get(&DataKey::WalletWasmHash).unwrap()
+    }
+    
+    // Derive the deployment salt from a passkey credential id
+    fn credential_salt(env: &Env, credential_id: &Bytes) -> BytesN<32> {
+        if credential_id.is_empty() {
+            panic!("Credential id must not be empty");
+        }
+        env.crypto().sha256(credential_id)
+    }
+}